@@ -1,29 +1,39 @@
 use std::collections::HashMap;
 use std::fs;
 use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
 use colored::Colorize;
 use indicatif::ProgressBar;
 use jwalk::WalkDir;
 use mago_allocator::prelude::LocalArena;
+use mago_span::Span;
 use mago_syntax::cst::Statement;
 
-use crate::config::Config;
+use crate::config::{Config, TimeBudget};
 use crate::debug_stats::{FileTimings, RuleTimings};
 use crate::file::File;
+use crate::git::{ChangedFiles, Revision};
 use crate::outputs::codeclimate::CodeClimate;
+use crate::outputs::csv::Csv;
 use crate::outputs::json::Json;
+use crate::outputs::ndjson::NdJson;
 use crate::outputs::sarif::Sarif;
 use crate::outputs::text::Text;
 use crate::outputs::Format;
 use crate::outputs::OutputFormatter;
+use crate::project_index::ProjectIndex;
 use crate::results::{Results, Violation};
+use crate::rules::guard::FileRun;
 use crate::rules::Rule;
 use crate::rules::{self};
 
+/// Source bytes the main pass parses into its arena before resetting it.
+pub(crate) const BATCH_BYTES: usize = 8 * 1024 * 1024;
+
 /// Print a verbose line. When a progress bar is active, route it through
 /// `ProgressBar::println` so the bar stays pinned to the bottom and the line
 /// scrolls above it; otherwise fall back to plain stderr.
@@ -89,15 +99,54 @@ pub fn scan_folder(
 
 pub struct Analyse {
     pub(crate) rules: HashMap<String, Box<dyn Rule>>,
+    /// When set, only these files are validated. Every scanned file is still
+    /// indexed so cross-file rules see the whole project. Staged files are read
+    /// from the git index rather than the disk.
+    pub(crate) changed_files: Option<ChangedFiles>,
+    /// When set, files are read from this git revision instead of the disk.
+    pub(crate) revision: Option<Revision>,
+    /// Index of the last scanned project, attached to every analysed file that
+    /// has none, such as an older revision or an editor buffer.
+    pub(crate) project: RwLock<Arc<ProjectIndex>>,
+    /// Directories indexed for their declarations only, such as `vendor/`. They
+    /// are neither validated nor counted, even below a scanned path.
+    pub(crate) types_only: Vec<PathBuf>,
+    /// Declarations of the types-only directories, built once by
+    /// `index_types_only` and the base of the index of every scan.
+    pub(crate) types_index: ProjectIndex,
+    /// How long rules may run on each file; see `FileRun`.
+    pub(crate) time_budget: TimeBudget,
 }
 
 impl Analyse {
     pub fn new(config: &Config) -> Self {
         Self {
-            rules: Self::get_active_rules(config),
+            time_budget: config.time_budget.clone(),
+            ..Self::from_rules(Self::get_active_rules(config))
+        }
+    }
+
+    pub(crate) fn from_rules(rules: HashMap<String, Box<dyn Rule>>) -> Self {
+        Self {
+            rules,
+            changed_files: None,
+            revision: None,
+            project: RwLock::default(),
+            types_only: Vec::new(),
+            types_index: ProjectIndex::default(),
+            time_budget: TimeBudget::default(),
+        }
+    }
+
+    pub(crate) fn set_project(&self, project: ProjectIndex) {
+        if let Ok(mut current) = self.project.write() {
+            *current = Arc::new(project);
         }
     }
 
+    /// Scan `path`. With `on_file`, each file's violations are handed to it as
+    /// soon as the file is analysed, instead of being kept in the results; only
+    /// the violations it returns as reported are counted.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn scan(
         &self,
@@ -108,6 +157,7 @@ impl Analyse {
         verbose: u8,
         collect_rule_metrics: bool,
         external_bar: Option<ProgressBar>,
+        mut on_file: Option<&mut dyn FnMut(String, Vec<Violation>) -> Vec<Violation>>,
     ) -> Results {
         let now = std::time::Instant::now();
         let mut results = Results::default();
@@ -146,55 +196,130 @@ impl Analyse {
         }
 
         let scan_path = path.clone();
-        let exclude_paths = config.exclude_paths.clone();
-        std::thread::spawn(move || {
-            let path = PathBuf::from(scan_path);
-            self::scan_folder(path, send, verbose, thread_bar, exclude_paths);
-        });
-
-        let arena = LocalArena::new();
+        let mut exclude_paths = config.exclude_paths.clone();
+        exclude_paths.extend(
+            self.types_only
+                .iter()
+                .map(|path| crate::paths::normalize_relative(path)),
+        );
 
-        // 1. Collect all files
-        let mut scanned_files: Vec<File<'_>> = Vec::new();
-        for (content, path) in recv {
-            if verbose >= 2 {
-                log_line(
-                    progress_bar.as_ref(),
-                    format!("[vv] parsing {}", path.display()),
-                );
+        // 1. Pre-pass (indexing). Starts from the types-only declarations, so
+        // that a project declaration replaces a dependency's one of the same name.
+        // Syntax trees and sources are dropped once indexed; only the paths of
+        // the files to validate are kept, and read again by the main pass.
+        let mut project = self.types_index.clone();
+        let mut arena = LocalArena::new();
+        let mut sources: Vec<PathBuf> = Vec::new();
+        std::thread::scope(|scope| {
+            match &self.revision {
+                Some(revision) => {
+                    let bar = thread_bar.clone();
+                    scope.spawn(move || {
+                        let path = Path::new(&scan_path);
+                        if let Err(msg) = revision.send_files(path, send, &exclude_paths) {
+                            log_line(bar.as_ref(), msg.red().to_string());
+                        }
+                    });
+                }
+                None => {
+                    scope.spawn(move || {
+                        let path = PathBuf::from(scan_path);
+                        self::scan_folder(path, send, verbose, thread_bar, exclude_paths);
+                    });
+                }
             }
-            scanned_files.push(File::new(&arena, path, content));
-        }
 
-        // 2. Pre-pass (indexing).
-        for file in &scanned_files {
-            if verbose >= 3 {
-                log_line(
-                    progress_bar.as_ref(),
-                    format!("[vvv] indexing {}", file.path.display()),
-                );
+            for (content, path) in recv {
+                let content = match self
+                    .changed_files
+                    .as_ref()
+                    .and_then(|changed| changed.staged_content(&path))
+                {
+                    Some(staged) => staged.to_string(),
+                    None => content,
+                };
+                if verbose >= 2 {
+                    log_line(
+                        progress_bar.as_ref(),
+                        format!("[vv] parsing {}", path.display()),
+                    );
+                }
+                {
+                    let file = File::new(&arena, path.clone(), content);
+                    if verbose >= 3 {
+                        log_line(
+                            progress_bar.as_ref(),
+                            format!("[vvv] indexing {}", file.path.display()),
+                        );
+                    }
+                    project.add_file(&file);
+                }
+                arena.reset();
+
+                let validate = match &self.changed_files {
+                    Some(changed) => changed.contains(&path),
+                    None => true,
+                };
+                if validate {
+                    sources.push(path);
+                } else if let Some(ref pb) = progress_bar {
+                    pb.inc(1);
+                }
             }
-            for rule in self.rules.values() {
-                rule.index_file(file);
+        });
+        if verbose >= 3 {
+            for class_like in project.class_likes() {
+                log_line(progress_bar.as_ref(), format!("[vvv] indexed {class_like}"));
+                for method in class_like.methods.values() {
+                    log_line(progress_bar.as_ref(), format!("[vvv]   {method}"));
+                }
             }
         }
+        self.set_project(project);
 
-        // 3. Main pass.
+        // 2. Main pass. Files are read and parsed again, a batch at a time into
+        // an arena that is reset in between, so memory is bounded by the batch size.
         let mut files = 0;
-        for mut file in scanned_files {
+        let mut batch_bytes = 0;
+        for path in sources {
+            let content = match self.read_source(&path) {
+                Ok(content) => content,
+                Err(msg) => {
+                    log_line(progress_bar.as_ref(), msg.red().to_string());
+                    continue;
+                }
+            };
+            if batch_bytes >= BATCH_BYTES {
+                arena.reset();
+                batch_bytes = 0;
+            }
+            batch_bytes += content.len();
+
+            if let Some(ref pb) = progress_bar {
+                pb.inc(1);
+            }
             if verbose >= 1 {
                 log_line(
                     progress_bar.as_ref(),
-                    format!("[v] analysing {}", file.path.display()),
+                    format!("[v] analysing {}", path.display()),
                 );
             }
-            if let Some(ref pb) = progress_bar {
-                pb.inc(1);
-            }
 
+            let mut file = File::new(&arena, path, content);
             let (violations, file_timings) = self.analyse_file(&mut file, collect_rule_metrics);
             let file_path = file.path.display().to_string();
-            results.add_file_violations(&file, violations);
+            match on_file.as_mut() {
+                Some(on_file) => {
+                    let reported = on_file(file_path.clone(), violations);
+                    for violation in reported.iter().filter(|v| !v.is_suppressed()) {
+                        *results
+                            .codes_count
+                            .entry(violation.rule.clone())
+                            .or_insert(0) += 1;
+                    }
+                }
+                None => results.add_file_violations(&file, violations),
+            }
 
             if let (Some(rt), Some(ft)) = (results.rule_timings.as_mut(), file_timings) {
                 rt.merge_file(file_path, ft);
@@ -209,10 +334,59 @@ impl Analyse {
 
         results.total_files_count = files;
         results.duration = Some(now.elapsed());
+        if let Some(rt) = results.rule_timings.as_mut() {
+            rt.peak_memory = crate::debug_stats::peak_memory();
+        }
 
         results
     }
 
+    /// Source of `path` as scanned: staged, at the revision or on disk.
+    fn read_source(&self, path: &Path) -> Result<String, String> {
+        if let Some(staged) = self
+            .changed_files
+            .as_ref()
+            .and_then(|changed| changed.staged_content(path))
+        {
+            return Ok(staged.to_string());
+        }
+        match &self.revision {
+            Some(revision) => revision.content(path),
+            None => {
+                fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))
+            }
+        }
+    }
+
+    /// Index the declarations of the `types_only` directories. Done once, before
+    /// scanning, as every scanned path shares them.
+    // Called from main.rs; dead_code is a false positive across crate targets.
+    #[allow(dead_code)]
+    pub(crate) fn index_types_only(&mut self, config: &Config, verbose: u8) {
+        let mut project = ProjectIndex::default();
+        for types_path in &self.types_only {
+            let (send, recv) = std::sync::mpsc::channel();
+            let walk_path = types_path.clone();
+            let exclude_paths = config.exclude_paths.clone();
+            std::thread::spawn(move || {
+                self::scan_folder(walk_path, send, verbose, None, exclude_paths);
+            });
+
+            let mut arena = LocalArena::new();
+            for (content, path) in recv {
+                // Only the index outlives the file.
+                let file = File::new(&arena, path, content);
+                if verbose >= 3 {
+                    log_line(None, format!("[vvv] indexing {}", file.path.display()));
+                }
+                project.add_file(&file);
+                drop(file);
+                arena.reset();
+            }
+        }
+        self.types_index = project;
+    }
+
     pub(crate) fn parse_config(config_path: String, output_format: &Format, quiet: bool) -> Config {
         let path = PathBuf::from(config_path);
         let default_config = Config::default();
@@ -286,6 +460,8 @@ impl Analyse {
             Format::json => Json::output(results),
             Format::sarif => Sarif::output(results),
             Format::codeclimate => CodeClimate::output(results),
+            Format::csv => Csv::output(results),
+            Format::ndjson => NdJson::output(results),
             _ => Text::output(results),
         };
     }
@@ -302,16 +478,47 @@ impl Analyse {
             None
         };
 
+        if file.project.is_none() {
+            file.project = self.project.read().ok().map(|project| Arc::clone(&project));
+        }
+
+        let mut run = FileRun::new(&self.time_budget);
+        for (code, rule) in &self.rules {
+            let rule_start = timings.as_ref().map(|_| Instant::now());
+
+            let validated = rule.do_validate(file);
+            if validated {
+                violations.append(&mut run.run(code, || rule.validate_file(file)));
+            }
+
+            if let Some(t) = timings.as_mut() {
+                let entry = t.entry(rule.get_code()).or_default();
+                entry.duration += rule_start.unwrap().elapsed();
+                entry.validated |= validated;
+            }
+        }
+
         if let Some(program) = file.ast {
             file.reference_counter.build_reference_counter(program);
             for statement in program.statements.iter() {
                 violations.append(&mut self.analyse_file_statement(
                     file,
                     statement,
+                    &mut run,
                     timings.as_mut(),
                 ));
             }
         }
+
+        for (code, failure) in run.failures() {
+            let message = failure.message(&code, &file.path);
+            violations.push(self.rules[&code].new_violation(file, message, Span::zero()));
+        }
+
+        let suppressions = file.inline_suppressions();
+        for violation in violations.iter_mut() {
+            violation.inline_suppressed = suppressions.iter().any(|s| s.covers(violation));
+        }
         (violations, timings)
     }
 
@@ -359,24 +566,31 @@ impl Analyse {
         ProgressBar::new(files_count as u64)
     }
 
-    pub fn analyse_file_statement<'a>(
+    pub(crate) fn analyse_file_statement<'a>(
         &self,
         file: &File<'a>,
         statement: &Statement<'a>,
+        run: &mut FileRun,
         mut timings: Option<&mut FileTimings>,
     ) -> Vec<Violation> {
         let mut violations = Vec::new();
+        let top_level = [statement];
+        let nested = rules::flatten_statements(statement);
 
-        for rule in self.rules.values() {
+        for (code, rule) in &self.rules {
             let rule_start = timings.as_ref().map(|_| Instant::now());
 
             let validated = rule.do_validate(file);
             let mut stmt_count = 0;
             if validated {
-                let flat = rule.flatten_statements_to_validate(statement);
-                stmt_count = flat.len();
-                for statement in flat {
-                    violations.append(&mut rule.validate(file, statement));
+                let statements = if rule.validate_nested_statements() {
+                    &nested[..]
+                } else {
+                    &top_level[..]
+                };
+                stmt_count = statements.len();
+                for statement in statements {
+                    violations.append(&mut run.run(code, || rule.validate(file, statement)));
                 }
             }
 
@@ -422,6 +636,141 @@ mod tests {
         assert!(!names.contains(&"Skip.php".to_string()));
     }
 
+    #[test]
+    fn types_only_paths_are_indexed_but_not_validated() {
+        let base =
+            std::env::temp_dir().join(format!("phanalist_types_only_{}", std::process::id()));
+        let vendor = base.join("vendor");
+        fs::create_dir_all(&vendor).unwrap();
+        fs::write(
+            base.join("Home.php"),
+            "<?php\nclass Home extends \\Vendor\\AbstractController {}\n",
+        )
+        .unwrap();
+        fs::write(
+            vendor.join("AbstractController.php"),
+            "<?php\nnamespace Vendor;\nabstract class AbstractController {}\n",
+        )
+        .unwrap();
+
+        let mut analyse = Analyse::new(&Config::default());
+        analyse.types_only.push(vendor);
+        analyse.index_types_only(&Config::default(), 0);
+        let results = analyse.scan(
+            base.display().to_string(),
+            &Config::default(),
+            false,
+            &Format::json,
+            0,
+            false,
+            None,
+            None,
+        );
+        fs::remove_dir_all(&base).ok();
+
+        assert_eq!(results.total_files_count, 1);
+        let project = analyse.project.read().unwrap();
+        assert!(project.get("Vendor\\AbstractController").is_some());
+        assert_eq!(
+            project.get("Home").and_then(|home| home.parent.as_deref()),
+            Some("Vendor\\AbstractController")
+        );
+    }
+
+    #[test]
+    fn streamed_violations_are_not_kept_in_results() {
+        let base = std::env::temp_dir().join(format!("phanalist_stream_{}", std::process::id()));
+        fs::create_dir_all(&base).unwrap();
+        for name in ["A.php", "B.php"] {
+            fs::write(
+                base.join(name),
+                "<?php\n$handle = @fopen('log.txt', 'r');\n",
+            )
+            .unwrap();
+        }
+
+        let config = Config {
+            enabled_rules: vec![crate::rules::e11::CODE.to_string()],
+            ..Default::default()
+        };
+        let analyse = Analyse::new(&config);
+        let mut streamed: Vec<(String, usize)> = Vec::new();
+        let mut on_file = |path: String, violations: Vec<Violation>| {
+            streamed.push((path, violations.len()));
+            violations
+        };
+        let results = analyse.scan(
+            base.display().to_string(),
+            &config,
+            false,
+            &Format::ndjson,
+            0,
+            false,
+            None,
+            Some(&mut on_file),
+        );
+        fs::remove_dir_all(&base).ok();
+
+        streamed.sort();
+        let names: Vec<(&str, usize)> = streamed
+            .iter()
+            .map(|(path, count)| (&path[path.len() - 5..], *count))
+            .collect();
+        assert_eq!(names, vec![("A.php", 1), ("B.php", 1)]);
+        assert_eq!(results.total_files_count, 2);
+        assert!(results.files.is_empty());
+        assert_eq!(results.codes_count.get(crate::rules::e11::CODE), Some(&2));
+    }
+
+    /// Panics on every statement.
+    struct Crashing;
+
+    impl Rule for Crashing {
+        fn get_code(&self) -> String {
+            String::from("E9999")
+        }
+
+        fn do_validate(&self, _file: &File<'_>) -> bool {
+            true
+        }
+
+        fn validate(&self, _file: &File<'_>, _statement: &Statement<'_>) -> Vec<Violation> {
+            panic!("unexpected node")
+        }
+    }
+
+    #[test]
+    fn crashing_rule_is_reported_and_others_still_run() {
+        let mut rules = Analyse::get_active_rules(&Config {
+            enabled_rules: vec![crate::rules::e11::CODE.to_string()],
+            ..Default::default()
+        });
+        rules.insert(String::from("E9999"), Box::new(Crashing));
+        let analyse = Analyse::from_rules(rules);
+
+        let arena = LocalArena::new();
+        let mut file = File::new(
+            &arena,
+            PathBuf::from("Quiet.php"),
+            String::from(
+                "<?php
+$handle = @fopen('log.txt', 'r');
+fclose($handle);
+",
+            ),
+        );
+        let (violations, _) = analyse.analyse_file(&mut file, false);
+
+        let rules: Vec<&str> = violations.iter().map(|v| v.rule.as_str()).collect();
+        assert_eq!(rules, vec!["E0011", "E9999"]);
+        let crash = &violations[1];
+        assert!(crash.is_error());
+        assert_eq!(
+            crash.message.render(),
+            "Rule E9999 crashed on Quiet.php and was skipped: unexpected node."
+        );
+    }
+
     fn get_all_codes() -> Vec<String> {
         vec![
             "RULE1".to_string(),
@@ -1,16 +1,22 @@
 //! Baseline support: freeze the current set of violations and, on later runs,
-//! report only new ones. Keyed on `(path, file, rule, message id)` with a count,
-//! so line shifts and reworded message text do not invalidate it.
-
-use std::collections::HashMap;
+//! report only new ones.
+//!
+//! Version 2 keys each entry on `(path, file, rule, message id, fingerprint)`
+//! with a count. The fingerprint hashes the violation's enclosing symbol and its
+//! whitespace-normalized source line, so line shifts and reworded message text
+//! do not invalidate it, while fixing one violation and introducing another of
+//! the same rule in the same file is still noticed. Version 1 files (no
+//! fingerprint) keep loading and match on `(path, file, rule, message id)` only.
+
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
 use crate::paths::normalize_relative;
-use crate::results::Results;
+use crate::results::{BaselineState, Message, Results, Violation};
 
-const VERSION: u32 = 1;
+const VERSION: u32 = 2;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BaselineEntry {
@@ -20,6 +26,12 @@ pub struct BaselineEntry {
     pub id: String,
     /// Message template, carried for human readability only. Not part of the key.
     pub message: String,
+    /// Enclosing symbol, carried for human readability only. Not part of the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// See [`fingerprint`]. Absent in version 1 baselines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
     pub count: usize,
 }
 
@@ -38,45 +50,81 @@ pub fn split_dir_file(relative: &str) -> (String, String) {
     }
 }
 
+/// Stable fingerprint of a violation: its enclosing symbol plus its source line
+/// with whitespace collapsed, hashed with 64-bit FNV-1a so the value does not
+/// depend on the Rust version the baseline was written with.
+pub fn fingerprint(violation: &Violation) -> String {
+    let line = violation
+        .line
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let symbol = violation.symbol.as_deref().unwrap_or_default();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in symbol.bytes().chain([0]).chain(line.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// `(path, file, rule, id, fingerprint)`
+type Key = (String, String, String, String, Option<String>);
+
 impl Baseline {
     /// Build a baseline by grouping a run's violations on
-    /// `(path, file, rule, id)` and counting each group. Entries are sorted so
-    /// the serialized file is deterministic.
+    /// `(path, file, rule, id, fingerprint)` and counting each group. Entries
+    /// are sorted so the serialized file is deterministic.
     pub fn from_results(results: &Results) -> Baseline {
-        // key -> (template, count)
-        let mut groups: HashMap<(String, String, String, String), (String, usize)> =
-            HashMap::new();
+        // key -> (template, symbol, count)
+        let mut groups: HashMap<Key, (String, Option<String>, usize)> = HashMap::new();
 
         for (path, violations) in &results.files {
             let (dir, file) = split_dir_file(&normalize_relative(Path::new(path)));
-            for violation in violations {
+            for violation in violations.iter().filter(|v| !v.is_suppressed()) {
                 let key = (
                     dir.clone(),
                     file.clone(),
                     violation.rule.clone(),
                     violation.message.id.clone(),
+                    Some(fingerprint(violation)),
                 );
-                let entry = groups
-                    .entry(key)
-                    .or_insert_with(|| (violation.message.template.clone(), 0));
-                entry.1 += 1;
+                let entry = groups.entry(key).or_insert_with(|| {
+                    (
+                        violation.message.template.clone(),
+                        violation.symbol.clone(),
+                        0,
+                    )
+                });
+                entry.2 += 1;
             }
         }
 
         let mut violations: Vec<BaselineEntry> = groups
             .into_iter()
-            .map(|((path, file, rule, id), (message, count))| BaselineEntry {
-                path,
-                file,
-                rule,
-                id,
-                message,
-                count,
-            })
+            .map(
+                |((path, file, rule, id, fingerprint), (message, symbol, count))| BaselineEntry {
+                    path,
+                    file,
+                    rule,
+                    id,
+                    message,
+                    symbol,
+                    fingerprint,
+                    count,
+                },
+            )
             .collect();
 
         violations.sort_by(|a, b| {
-            (&a.path, &a.file, &a.rule, &a.id).cmp(&(&b.path, &b.file, &b.rule, &b.id))
+            (&a.path, &a.file, &a.rule, &a.id, &a.fingerprint).cmp(&(
+                &b.path,
+                &b.file,
+                &b.rule,
+                &b.id,
+                &b.fingerprint,
+            ))
         });
 
         Baseline {
@@ -95,72 +143,271 @@ impl Baseline {
 
     pub fn load(path: &Path) -> std::io::Result<Baseline> {
         let text = std::fs::read_to_string(path)?;
-        Self::from_json(&text)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        let baseline = Self::from_json(&text)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if baseline.version > VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "baseline version {} is newer than the supported version {VERSION}",
+                    baseline.version
+                ),
+            ));
+        }
+        Ok(baseline)
     }
 
     pub fn save(&self, path: &PathBuf) -> std::io::Result<()> {
         std::fs::write(path, self.to_pretty_json())
     }
 
-    /// Drop from `results` every violation already accounted for in the
-    /// baseline, leaving only the surplus (new) ones, and recompute counts.
-    pub fn filter(&self, results: &mut Results) {
-        // key -> baseline budget (how many of this key are already known)
-        let budget: HashMap<(&str, &str, &str, &str), usize> = self
-            .violations
-            .iter()
-            .map(|e| {
-                (
-                    (
-                        e.path.as_str(),
-                        e.file.as_str(),
-                        e.rule.as_str(),
-                        e.id.as_str(),
-                    ),
-                    e.count,
-                )
-            })
-            .collect();
+    /// The fingerprint a violation is matched on: none for version 1
+    /// baselines, which predate fingerprints.
+    fn key_fingerprint(&self, violation: &Violation) -> Option<String> {
+        if self.version >= 2 {
+            Some(fingerprint(violation))
+        } else {
+            None
+        }
+    }
+
+    /// Mark every violation in `results` as `New` or `Unchanged` relative to
+    /// the baseline instead of dropping the known ones, and add an `Absent`
+    /// violation (without a position) for each baselined occurrence that no
+    /// longer shows up in a scanned file. Counts only include new violations.
+    ///
+    /// Entries for files that were not part of this scan are left alone: they
+    /// may belong to another `--src` path.
+    pub fn mark(&self, results: &mut Results) {
+        // (path, file) -> entries, so absent occurrences are found per file.
+        let mut by_file: HashMap<(&str, &str), Vec<&BaselineEntry>> = HashMap::new();
+        for entry in &self.violations {
+            by_file
+                .entry((entry.path.as_str(), entry.file.as_str()))
+                .or_default()
+                .push(entry);
+        }
 
         for (path, violations) in results.files.iter_mut() {
             let (dir, file) = split_dir_file(&normalize_relative(Path::new(path)));
-            // How many of each key we have suppressed so far in this file.
-            let mut seen: HashMap<(String, String), usize> = HashMap::new();
-
-            violations.retain(|violation| {
-                let key = (violation.rule.clone(), violation.message.id.clone());
-                let count = seen.entry(key).or_insert(0);
-                *count += 1;
+            let entries = by_file
+                .get(&(dir.as_str(), file.as_str()))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            // (rule, id, fingerprint) -> baseline budget for this file.
+            let budget: HashMap<(&str, &str, Option<&str>), usize> = entries
+                .iter()
+                .map(|e| {
+                    (
+                        (e.rule.as_str(), e.id.as_str(), e.fingerprint.as_deref()),
+                        e.count,
+                    )
+                })
+                .collect();
+
+            // How many of each key we have matched so far in this file.
+            let mut seen: HashMap<(String, String, Option<String>), usize> = HashMap::new();
+
+            // Silenced violations take nothing from the baseline.
+            for violation in violations.iter_mut().filter(|v| !v.inline_suppressed) {
+                let fingerprint = self.key_fingerprint(violation);
                 let allowed = budget
                     .get(&(
-                        dir.as_str(),
-                        file.as_str(),
                         violation.rule.as_str(),
                         violation.message.id.as_str(),
+                        fingerprint.as_deref(),
                     ))
                     .copied()
                     .unwrap_or(0);
-                // Keep only the surplus beyond the baselined count.
-                *count > allowed
-            });
+                let key = (
+                    violation.rule.clone(),
+                    violation.message.id.clone(),
+                    fingerprint,
+                );
+                let count = seen.entry(key).or_insert(0);
+                *count += 1;
+                // Only the surplus beyond the baselined count is new.
+                violation.baseline_state = Some(if *count > allowed {
+                    BaselineState::New
+                } else {
+                    BaselineState::Unchanged
+                });
+            }
+
+            for entry in entries {
+                let found = seen
+                    .get(&(
+                        entry.rule.clone(),
+                        entry.id.clone(),
+                        entry.fingerprint.clone(),
+                    ))
+                    .copied()
+                    .unwrap_or(0);
+                for _ in found..entry.count {
+                    violations.push(Violation {
+                        rule: entry.rule.clone(),
+                        line: String::new(),
+                        message: Message::new(entry.id.clone(), entry.message.clone()),
+                        start_line: 0,
+                        start_column: 0,
+                        end_line: 0,
+                        end_column: 0,
+                        symbol: entry.symbol.clone(),
+                        baseline_state: Some(BaselineState::Absent),
+                        inline_suppressed: false,
+                    });
+                }
+            }
         }
 
-        // Recompute counts from what survived.
-        let mut codes_count: HashMap<String, i64> = HashMap::new();
-        for violations in results.files.values() {
-            for violation in violations {
-                *codes_count.entry(violation.rule.clone()).or_insert(0) += 1;
+        results.recount_codes();
+    }
+
+    /// Drop from `results` every violation already accounted for in the
+    /// baseline, leaving only the surplus (new) ones, and recompute counts.
+    pub fn filter(&self, results: &mut Results) {
+        self.mark(results);
+
+        for violations in results.files.values_mut() {
+            violations.retain(|violation| violation.baseline_state == Some(BaselineState::New));
+            // Filtered output looks exactly like a run without a baseline.
+            for violation in violations.iter_mut() {
+                violation.baseline_state = None;
             }
         }
-        results.codes_count = codes_count;
     }
+
+    /// How many violations each key of a scanned file actually has, plus the
+    /// set of scanned `(path, file)` pairs.
+    fn actual_counts(&self, results: &Results) -> (HashMap<Key, usize>, HashSet<(String, String)>) {
+        let mut counts: HashMap<Key, usize> = HashMap::new();
+        let mut scanned = HashSet::new();
+
+        for (path, violations) in &results.files {
+            let (dir, file) = split_dir_file(&normalize_relative(Path::new(path)));
+            for violation in violations.iter().filter(|v| !v.is_suppressed()) {
+                let key = (
+                    dir.clone(),
+                    file.clone(),
+                    violation.rule.clone(),
+                    violation.message.id.clone(),
+                    self.key_fingerprint(violation),
+                );
+                *counts.entry(key).or_insert(0) += 1;
+            }
+            scanned.insert((dir, file));
+        }
+
+        (counts, scanned)
+    }
+
+    /// Entries of scanned files that allow more violations than are left, i.e.
+    /// violations that were fixed and should be removed from the baseline.
+    /// Must be called with unfiltered results.
+    pub fn fixed_entries(&self, results: &Results) -> Vec<FixedEntry> {
+        let (counts, scanned) = self.actual_counts(results);
+
+        self.violations
+            .iter()
+            .filter(|e| scanned.contains(&(e.path.clone(), e.file.clone())))
+            .filter_map(|e| {
+                let found = counts.get(&entry_key(e)).copied().unwrap_or(0);
+                (found < e.count).then(|| FixedEntry {
+                    entry: e.clone(),
+                    found,
+                })
+            })
+            .collect()
+    }
+
+    /// Ratchet the baseline down to `results`: lower each count to the
+    /// violations still present and drop entries that were fully fixed, or
+    /// whose file no longer exists. Unlike [`Baseline::from_results`], new
+    /// violations are never added. Must be called with unfiltered results.
+    ///
+    /// Entries of files that were not scanned are only dropped when they lie
+    /// inside one of the `analysed` paths and are gone from disk; entries
+    /// outside them belong to another scan and are kept.
+    pub fn prune(&self, results: &Results, analysed: &[String]) -> Baseline {
+        let (counts, scanned) = self.actual_counts(results);
+        let roots: Vec<String> = analysed
+            .iter()
+            .map(|path| normalize_relative(Path::new(path)))
+            .collect();
+
+        let violations = self
+            .violations
+            .iter()
+            .filter_map(|e| {
+                let count = if scanned.contains(&(e.path.clone(), e.file.clone())) {
+                    let found = counts.get(&entry_key(e)).copied().unwrap_or(0);
+                    e.count.min(found)
+                } else {
+                    let relative = if e.path.is_empty() {
+                        e.file.clone()
+                    } else {
+                        format!("{}/{}", e.path, e.file)
+                    };
+                    let analysed = roots.iter().any(|root| is_within(&relative, root));
+                    if analysed && !Path::new(&relative).exists() {
+                        0
+                    } else {
+                        // Not part of this scan, or outside it; keep it as is.
+                        e.count
+                    }
+                };
+
+                (count > 0).then(|| BaselineEntry { count, ..e.clone() })
+            })
+            .collect();
+
+        Baseline {
+            version: self.version,
+            violations,
+        }
+    }
+}
+
+/// A baseline entry that allows more violations than the scan found.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FixedEntry {
+    #[serde(flatten)]
+    pub entry: BaselineEntry,
+    /// Violations of this entry still present; `entry.count - found` were fixed.
+    pub found: usize,
+}
+
+impl FixedEntry {
+    pub fn fixed(&self) -> usize {
+        self.entry.count - self.found
+    }
+}
+
+/// True if the normalized `relative` path is `root` itself or lies below it.
+/// An empty or `.` root is the working directory and contains every relative
+/// path that does not climb out of it.
+fn is_within(relative: &str, root: &str) -> bool {
+    let root = root.trim_end_matches('/');
+    if root.is_empty() || root == "." {
+        return !relative.starts_with("../") && !Path::new(relative).is_absolute();
+    }
+    relative == root || relative.starts_with(&format!("{root}/"))
+}
+
+fn entry_key(entry: &BaselineEntry) -> Key {
+    (
+        entry.path.clone(),
+        entry.file.clone(),
+        entry.rule.clone(),
+        entry.id.clone(),
+        entry.fingerprint.clone(),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::results::{Message, Violation};
 
     fn vio(rule: &str, id: &str, template: &str) -> Violation {
         Violation {
@@ -171,6 +418,17 @@ mod tests {
             start_column: 0,
             end_line: 0,
             end_column: 0,
+            symbol: None,
+            baseline_state: None,
+            inline_suppressed: false,
+        }
+    }
+
+    fn vio_at(rule: &str, id: &str, line: &str, symbol: &str) -> Violation {
+        Violation {
+            line: line.to_string(),
+            symbol: Some(symbol.to_string()),
+            ..vio(rule, id, "m")
         }
     }
 
@@ -309,4 +567,229 @@ mod tests {
         assert_eq!(results.files.get("./src/Foo.php").unwrap().len(), 1);
         assert_eq!(*results.codes_count.get("E0009").unwrap(), 1);
     }
+
+    #[test]
+    fn mark_keeps_baselined_violations_as_unchanged() {
+        let baseline_results =
+            results_with(vec![("./src/Foo.php", vec![vio("E0009", "E0009:c", "m")])]);
+        let baseline = Baseline::from_results(&baseline_results);
+
+        let mut results = results_with(vec![(
+            "./src/Foo.php",
+            vec![vio("E0009", "E0009:c", "m"), vio("E0009", "E0009:c", "m")],
+        )]);
+
+        baseline.mark(&mut results);
+
+        let states: Vec<Option<BaselineState>> = results.files["./src/Foo.php"]
+            .iter()
+            .map(|v| v.baseline_state)
+            .collect();
+        assert_eq!(
+            states,
+            vec![Some(BaselineState::Unchanged), Some(BaselineState::New)]
+        );
+        assert_eq!(*results.codes_count.get("E0009").unwrap(), 1);
+    }
+
+    #[test]
+    fn mark_adds_absent_entries_for_scanned_files() {
+        let baseline_results = results_with(vec![
+            (
+                "./src/Foo.php",
+                vec![
+                    vio("E0009", "E0009:c", "m"),
+                    vio("E0005", "E0005:name", "n"),
+                ],
+            ),
+            ("./src/Other.php", vec![vio("E0009", "E0009:c", "m")]),
+        ]);
+        let baseline = Baseline::from_results(&baseline_results);
+
+        let mut results =
+            results_with(vec![("./src/Foo.php", vec![vio("E0009", "E0009:c", "m")])]);
+
+        baseline.mark(&mut results);
+
+        let violations = &results.files["./src/Foo.php"];
+        assert_eq!(violations.len(), 2);
+        let absent = violations
+            .iter()
+            .find(|v| v.baseline_state == Some(BaselineState::Absent))
+            .unwrap();
+        assert_eq!(absent.rule, "E0005");
+        assert_eq!(absent.message.id, "E0005:name");
+        // Files outside the scan are not reported as absent.
+        assert_eq!(results.files.len(), 1);
+        assert!(!results.has_any_violations());
+    }
+
+    #[test]
+    fn filter_clears_baseline_state_on_survivors() {
+        let baseline = Baseline {
+            version: VERSION,
+            violations: vec![],
+        };
+        let mut results =
+            results_with(vec![("./src/Foo.php", vec![vio("E0009", "E0009:c", "m")])]);
+
+        baseline.filter(&mut results);
+
+        assert_eq!(results.files["./src/Foo.php"][0].baseline_state, None);
+    }
+
+    #[test]
+    fn fingerprint_ignores_whitespace_but_not_symbol() {
+        let a = vio_at("E0011", "E0011:x", "    $a = @foo();", "App\\Foo::bar");
+        let b = vio_at("E0011", "E0011:x", "$a  =  @foo();\t", "App\\Foo::bar");
+        let c = vio_at("E0011", "E0011:x", "$a = @foo();", "App\\Foo::baz");
+
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+        assert_ne!(fingerprint(&a), fingerprint(&c));
+    }
+
+    #[test]
+    fn filter_reports_new_violation_replacing_a_fixed_one() {
+        let baseline_results = results_with(vec![(
+            "./src/Foo.php",
+            vec![vio_at("E0011", "E0011:x", "@old();", "App\\Foo::bar")],
+        )]);
+        let baseline = Baseline::from_results(&baseline_results);
+
+        // The baselined violation was fixed and a different one of the same
+        // rule appeared: the count is unchanged, but the new one is reported.
+        let mut results = results_with(vec![(
+            "./src/Foo.php",
+            vec![vio_at("E0011", "E0011:x", "@new();", "App\\Foo::bar")],
+        )]);
+
+        baseline.filter(&mut results);
+
+        assert_eq!(results.files["./src/Foo.php"].len(), 1);
+        assert_eq!(results.files["./src/Foo.php"][0].line, "@new();");
+    }
+
+    #[test]
+    fn version_1_baseline_still_loads_and_matches_on_count() {
+        let json = r#"{
+  "version": 1,
+  "violations": [
+    {
+      "path": "src",
+      "file": "Foo.php",
+      "rule": "E0011",
+      "id": "E0011:x",
+      "message": "m",
+      "count": 1
+    }
+  ]
+}"#;
+        let baseline = Baseline::from_json(json).unwrap();
+        assert_eq!(baseline.violations[0].fingerprint, None);
+
+        let mut results = results_with(vec![(
+            "./src/Foo.php",
+            vec![vio_at("E0011", "E0011:x", "@new();", "App\\Foo::bar")],
+        )]);
+
+        baseline.filter(&mut results);
+
+        assert!(results.files["./src/Foo.php"].is_empty());
+    }
+
+    #[test]
+    fn load_rejects_newer_version() {
+        let path =
+            std::env::temp_dir().join(format!("phanalist_bl_v99_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"version": 99, "violations": []}"#).unwrap();
+        let err = Baseline::load(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn fixed_entries_reports_entries_with_fewer_violations_left() {
+        let baseline_results = results_with(vec![(
+            "./src/Foo.php",
+            vec![
+                vio("E0009", "E0009:c", "m"),
+                vio("E0009", "E0009:c", "m"),
+                vio("E0005", "E0005:name", "n"),
+            ],
+        )]);
+        let baseline = Baseline::from_results(&baseline_results);
+
+        let results = results_with(vec![
+            (
+                "./src/Foo.php",
+                vec![
+                    vio("E0009", "E0009:c", "m"),
+                    vio("E0005", "E0005:name", "n"),
+                ],
+            ),
+            ("./src/Bar.php", vec![]),
+        ]);
+
+        let fixed = baseline.fixed_entries(&results);
+
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed[0].entry.rule, "E0009");
+        assert_eq!(fixed[0].found, 1);
+        assert_eq!(fixed[0].fixed(), 1);
+    }
+
+    #[test]
+    fn prune_lowers_counts_and_never_adds_violations() {
+        let baseline_results = results_with(vec![(
+            "./src/Foo.php",
+            vec![
+                vio("E0009", "E0009:c", "m"),
+                vio("E0009", "E0009:c", "m"),
+                vio("E0005", "E0005:name", "n"),
+            ],
+        )]);
+        let baseline = Baseline::from_results(&baseline_results);
+
+        let results = results_with(vec![(
+            "./src/Foo.php",
+            vec![vio("E0009", "E0009:c", "m"), vio("E0011", "E0011:x", "new")],
+        )]);
+
+        let pruned = baseline.prune(&results, &["./src".to_string()]);
+
+        assert_eq!(pruned.version, baseline.version);
+        assert_eq!(pruned.violations.len(), 1);
+        assert_eq!(pruned.violations[0].rule, "E0009");
+        assert_eq!(pruned.violations[0].count, 1);
+    }
+
+    #[test]
+    fn prune_drops_entries_of_deleted_files_only() {
+        let baseline = Baseline::from_results(&results_with(vec![
+            ("./src/lib.rs", vec![vio("E0009", "E0009:c", "m")]),
+            ("./no_such_dir/Gone.php", vec![vio("E0009", "E0009:c", "m")]),
+        ]));
+
+        let pruned = baseline.prune(&Results::default(), &[".".to_string()]);
+
+        assert_eq!(pruned.violations.len(), 1);
+        assert_eq!(pruned.violations[0].file, "lib.rs");
+    }
+
+    #[test]
+    fn prune_keeps_entries_outside_the_analysed_paths() {
+        let baseline = Baseline::from_results(&results_with(vec![
+            ("./no_such_dir/Gone.php", vec![vio("E0009", "E0009:c", "m")]),
+            ("./other/Gone.php", vec![vio("E0009", "E0009:c", "m")]),
+        ]));
+
+        // Run from elsewhere, the scan sees none of the baseline's files.
+        let pruned = baseline.prune(&Results::default(), &["../src".to_string()]);
+        assert_eq!(pruned.violations.len(), 2);
+
+        let pruned = baseline.prune(&Results::default(), &["./other/".to_string()]);
+        assert_eq!(pruned.violations.len(), 1);
+        assert_eq!(pruned.violations[0].path, "no_such_dir");
+    }
 }
@@ -0,0 +1,132 @@
+//! `CODEOWNERS` lookup for grouping the engineer report by team or owner.
+//!
+//! Patterns follow gitignore rules, as on GitHub and GitLab: the last matching
+//! line wins, and a pattern matching a directory owns everything below it.
+
+use std::path::{Path, PathBuf};
+
+use gix::bstr::{BStr, ByteSlice};
+use gix::glob::pattern::Case;
+use gix::glob::wildmatch::Mode;
+use gix::glob::Pattern;
+
+/// Where `CODEOWNERS` is looked up, in the order GitHub searches them.
+const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+pub struct CodeOwners {
+    rules: Vec<(Pattern, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Read the first `CODEOWNERS` file found below `workdir`.
+    pub fn discover(workdir: &Path) -> Option<(PathBuf, Self)> {
+        LOCATIONS.iter().map(|l| workdir.join(l)).find_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            Some((path, Self::parse(&content)))
+        })
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                // GitLab section headers (`[Section]`) carry no pattern.
+                if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                    return None;
+                }
+                let mut fields = line.split_whitespace();
+                let pattern = Pattern::from_bytes_without_negation(fields.next()?.as_bytes())?;
+                let owners = fields
+                    .take_while(|f| !f.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some((pattern, owners))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Owners of a repository-relative path; empty when no line matches or the
+    /// matching line lists nobody.
+    pub fn owners(&self, relative_path: &str) -> &[String] {
+        let path = relative_path.trim_start_matches("./").replace('\\', "/");
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| matches(pattern, &path))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Match the file itself and every directory containing it.
+fn matches(pattern: &Pattern, path: &str) -> bool {
+    let is_match = |candidate: &str, is_dir: bool| {
+        let candidate: &BStr = candidate.as_bytes().as_bstr();
+        pattern.matches_repo_relative_path(
+            candidate,
+            candidate.rfind_byte(b'/').map(|p| p + 1),
+            Some(is_dir),
+            Case::Sensitive,
+            Mode::NO_MATCH_SLASH_LITERAL,
+        )
+    };
+
+    is_match(path, false)
+        || path
+            .match_indices('/')
+            .any(|(i, _)| is_match(&path[..i], true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*                   @acme/platform
+/src/Billing/       @acme/payments @jane
+*.yaml              @ops  # config
+docs/**             @writer
+/src/Billing/Legacy/
+";
+
+    #[test]
+    fn last_matching_line_wins() {
+        let owners = CodeOwners::parse(CODEOWNERS);
+
+        assert_eq!(owners.owners("src/Kernel.php"), ["@acme/platform"]);
+        assert_eq!(
+            owners.owners("./src/Billing/Invoice.php"),
+            ["@acme/payments", "@jane"]
+        );
+        assert_eq!(owners.owners("src/Billing/config.yaml"), ["@ops"]);
+        assert_eq!(owners.owners("docs/api/index.md"), ["@writer"]);
+        assert!(owners.owners("src/Billing/Legacy/Old.php").is_empty());
+    }
+
+    #[test]
+    fn anchored_directory_does_not_match_nested_paths() {
+        let owners = CodeOwners::parse("/src/Billing/ @acme/payments\n");
+
+        assert_eq!(owners.owners("src/Billing/Invoice.php"), ["@acme/payments"]);
+        assert!(owners.owners("lib/src/Billing/Invoice.php").is_empty());
+        assert!(owners.owners("src/BillingReport.php").is_empty());
+    }
+
+    #[test]
+    fn discover_prefers_github_directory() {
+        let dir = std::env::temp_dir().join(format!("phanalist_codeowners_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join(".github")).unwrap();
+        std::fs::write(dir.join("CODEOWNERS"), "* @root\n").unwrap();
+        std::fs::write(dir.join(".github/CODEOWNERS"), "* @github\n").unwrap();
+
+        let found = CodeOwners::discover(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+
+        let (path, owners) = found.unwrap();
+        assert!(path.ends_with(".github/CODEOWNERS"));
+        assert_eq!(owners.owners("a.php"), ["@github"]);
+    }
+}
@@ -0,0 +1,125 @@
+//! `composer.json` lookup: the autoload roots a project keeps its sources in,
+//! and the directory its dependencies are installed to.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+pub struct Composer {
+    /// Directory holding `composer.json`.
+    root: PathBuf,
+    /// PSR-4 directories and classmap entries of `autoload` and `autoload-dev`.
+    autoload: Vec<String>,
+    vendor_dir: String,
+}
+
+impl Composer {
+    /// Read `composer.json` in `dir`; `None` when there is none.
+    pub fn discover(dir: &Path) -> Result<Option<Self>, String> {
+        let path = dir.join("composer.json");
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(dir, &content)
+                .map(Some)
+                .map_err(|e| format!("Invalid {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn parse(root: &Path, content: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+
+        let mut autoload = Vec::new();
+        for section in ["autoload", "autoload-dev"].iter().filter_map(|s| json.get(s)) {
+            if let Some(psr4) = section.get("psr-4").and_then(Value::as_object) {
+                autoload.extend(psr4.values().flat_map(strings));
+            }
+            if let Some(classmap) = section.get("classmap") {
+                autoload.extend(strings(classmap));
+            }
+        }
+
+        let vendor_dir = json
+            .pointer("/config/vendor-dir")
+            .and_then(Value::as_str)
+            .unwrap_or("vendor")
+            .to_string();
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            autoload,
+            vendor_dir,
+        })
+    }
+
+    /// Existing autoload directories and files. Entries below another entry
+    /// are dropped, since scanning a directory covers everything in it.
+    pub fn source_roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = self
+            .autoload
+            .iter()
+            .map(|entry| match entry.trim_end_matches('/') {
+                "" | "." => self.root.clone(),
+                entry => self.root.join(entry),
+            })
+            .filter(|path| path.exists())
+            .collect();
+        roots.sort();
+        roots.dedup();
+
+        let all = roots.clone();
+        roots.retain(|root| !all.iter().any(|other| other != root && root.starts_with(other)));
+        roots
+    }
+
+    /// Where dependencies are installed, `vendor/` unless `config.vendor-dir` says otherwise.
+    pub fn vendor_dir(&self) -> PathBuf {
+        self.root.join(&self.vendor_dir)
+    }
+}
+
+/// A path, or a list of paths.
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_roots_from_psr4_and_classmap() {
+        let composer = Composer::parse(
+            Path::new("."),
+            r#"{
+                "autoload": {
+                    "psr-4": {"App\\": "src/", "Lib\\": ["src/rules", "missing/"]},
+                    "classmap": ["tests/fixtures"]
+                },
+                "autoload-dev": {"psr-4": {"App\\Tests\\": "tests/"}},
+                "config": {"vendor-dir": "lib/vendor"}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            composer.source_roots(),
+            vec![PathBuf::from("./src"), PathBuf::from("./tests")]
+        );
+        assert_eq!(composer.vendor_dir(), PathBuf::from("./lib/vendor"));
+    }
+
+    #[test]
+    fn missing_composer_json_is_not_an_error() {
+        let dir = std::env::temp_dir().join("phanalist_no_composer");
+        assert!(matches!(Composer::discover(&dir), Ok(None)));
+    }
+}
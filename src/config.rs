@@ -16,6 +16,32 @@ pub struct Config {
     /// prefixes (`var/cache`) or globs (`**/*.generated.php`).
     #[serde(default)]
     pub exclude_paths: Vec<String>,
+    /// Canonical author name for the engineer report, mapped from the names and
+    /// emails that person commits under. Applied after the repository `.mailmap`.
+    #[serde(default)]
+    pub identities: HashMap<String, Vec<String>>,
+    /// How long rules may run on one file before they are stopped.
+    #[serde(default)]
+    pub time_budget: TimeBudget,
+}
+
+/// Milliseconds one rule, and all rules together, may spend on a file. A rule
+/// over budget is stopped and reported instead of holding up the scan. 0
+/// disables the limit.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct TimeBudget {
+    pub per_rule_ms: u64,
+    pub per_file_ms: u64,
+}
+
+impl Default for TimeBudget {
+    fn default() -> Self {
+        TimeBudget {
+            per_rule_ms: 10_000,
+            per_file_ms: 30_000,
+        }
+    }
 }
 
 impl Default for Config {
@@ -106,6 +132,8 @@ impl Default for Config {
             disable_rules,
             rules,
             exclude_paths: vec![],
+            identities: HashMap::new(),
+            time_budget: TimeBudget::default(),
         }
     }
 }
@@ -143,4 +171,23 @@ mod tests {
         let config: Config = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(config.exclude_paths, vec!["var/cache".to_string()]);
     }
+
+    #[test]
+    fn identities_parsed_from_yaml() {
+        let yaml = "enabled_rules: []\ndisable_rules: []\nrules: {}\nidentities:\n  Jane Doe:\n    - jane@old.example.com\n    - jdoe\n";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.identities["Jane Doe"],
+            vec!["jane@old.example.com".to_string(), "jdoe".to_string()]
+        );
+    }
+
+    #[test]
+    fn time_budget_fills_in_missing_limits() {
+        let yaml =
+            "enabled_rules: []\ndisable_rules: []\nrules: {}\ntime_budget:\n  per_rule_ms: 500\n";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.time_budget.per_rule_ms, 500);
+        assert_eq!(config.time_budget.per_file_ms, 30_000);
+    }
 }
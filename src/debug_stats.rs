@@ -22,6 +22,23 @@ pub type FileTimings = HashMap<String, FileRuleMetric>;
 #[derive(Debug, Default, Clone)]
 pub struct RuleTimings {
     pub per_file: HashMap<String, Vec<(String, FileRuleMetric)>>,
+    /// Peak resident memory of the process when the scan finished, in bytes.
+    pub peak_memory: Option<u64>,
+}
+
+/// Peak resident memory of the process in bytes, where the platform reports
+/// it (`VmHWM` on Linux).
+pub fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
 }
 
 /// Computed, display-ready view for one rule.
@@ -72,7 +89,13 @@ impl RuleTimings {
         }
 
         println!();
-        println!("Rule debug ({total_files} files):");
+        match self.peak_memory {
+            Some(bytes) => println!(
+                "Rule debug ({total_files} files, peak memory {:.1} MiB):",
+                bytes as f64 / (1024.0 * 1024.0)
+            ),
+            None => println!("Rule debug ({total_files} files):"),
+        }
 
         if show_timing {
             let mut by_p99 = stats.clone();
@@ -346,4 +369,10 @@ mod tests {
         assert_eq!(durations, vec![6, 5, 4, 3, 2]);
         assert_eq!(stat.slowest[0].0, "b.php");
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn peak_memory_is_reported_on_linux() {
+        assert!(peak_memory().is_some_and(|bytes| bytes > 0));
+    }
 }
@@ -10,10 +10,11 @@ use indicatif::ProgressBar;
 use serde::{Deserialize, Serialize};
 
 use crate::analyse::Analyse;
+use crate::codeowners::CodeOwners;
 use crate::config::Config;
 use crate::file::File;
 use crate::outputs::Format;
-use crate::results::{EngineerReport, Violation};
+use crate::results::{CommitViolation, EngineerReport, Violation};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct BlameConfig {
@@ -24,6 +25,121 @@ pub struct BlameConfig {
     pub filter_authors: Vec<String>,
     pub filter_rules: Vec<String>,
     pub sort_by: String,
+    /// Canonical name to the author names and emails it stands for.
+    pub identities: HashMap<String, Vec<String>>,
+    pub group_by: GroupBy,
+    /// With `since`, also credit each change to the commit that made it.
+    pub track_commits: bool,
+}
+
+/// What the rows of the engineer report stand for.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The author of the violating lines.
+    #[default]
+    Engineer,
+    /// The `@org/team` owners of the file in `CODEOWNERS`.
+    Team,
+    /// Every owner of the file in `CODEOWNERS`, people and teams.
+    Owner,
+    /// The directory containing the file.
+    Directory,
+}
+
+impl std::str::FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "engineer" | "author" => Ok(GroupBy::Engineer),
+            "team" => Ok(GroupBy::Team),
+            "owner" => Ok(GroupBy::Owner),
+            "directory" | "dir" => Ok(GroupBy::Directory),
+            _ => Err(format!(
+                "Invalid --group-by \"{input}\": use engineer, team, owner or directory"
+            )),
+        }
+    }
+}
+
+impl GroupBy {
+    /// Column header and report title prefix.
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroupBy::Engineer => "Engineer",
+            GroupBy::Team => "Team",
+            GroupBy::Owner => "Owner",
+            GroupBy::Directory => "Directory",
+        }
+    }
+
+    pub fn plural(&self) -> &'static str {
+        match self {
+            GroupBy::Engineer => "Engineers",
+            GroupBy::Team => "Teams",
+            GroupBy::Owner => "Owners",
+            GroupBy::Directory => "Directories",
+        }
+    }
+}
+
+/// Turns an attributed violation into the report rows it counts towards.
+struct Grouping {
+    group_by: GroupBy,
+    code_owners: Option<CodeOwners>,
+    workdir: PathBuf,
+}
+
+impl Grouping {
+    fn new(group_by: GroupBy, workdir: PathBuf) -> Result<Self, String> {
+        let code_owners = match group_by {
+            GroupBy::Team | GroupBy::Owner => match CodeOwners::discover(&workdir) {
+                Some((_, owners)) => Some(owners),
+                None => {
+                    return Err(format!(
+                        "--group-by {} requires a CODEOWNERS file in the repository root, .github/ or docs/",
+                        group_by.label().to_lowercase()
+                    ))
+                }
+            },
+            GroupBy::Engineer | GroupBy::Directory => None,
+        };
+        let workdir = workdir.canonicalize().unwrap_or(workdir);
+        Ok(Self {
+            group_by,
+            code_owners,
+            workdir,
+        })
+    }
+
+    fn keys(&self, file_path: &str, author: &str) -> Vec<String> {
+        if self.group_by == GroupBy::Engineer {
+            return vec![author.to_string()];
+        }
+
+        let relative = crate::git::repo_relative(&self.workdir, Path::new(file_path))
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|| crate::paths::normalize_relative(Path::new(file_path)));
+
+        let owners = self
+            .code_owners
+            .as_ref()
+            .map(|o| o.owners(&relative))
+            .unwrap_or_default();
+        let keys: Vec<String> = match self.group_by {
+            GroupBy::Team => owners.iter().filter(|o| o.contains('/')).cloned().collect(),
+            GroupBy::Owner => owners.to_vec(),
+            _ => match relative.rfind('/') {
+                Some(pos) => vec![relative[..pos].to_string()],
+                None => vec![".".to_string()],
+            },
+        };
+        if keys.is_empty() {
+            vec!["(unowned)".to_string()]
+        } else {
+            keys
+        }
+    }
 }
 
 /// Parse a human-readable or absolute date string into a timestamp.
@@ -88,6 +204,43 @@ pub fn is_author_excluded(name: &str, email: &str, exclude: &[String]) -> bool {
     })
 }
 
+/// Merges the names and emails one person commits under: the repository
+/// `.mailmap` is applied first, then the `identities` config section.
+struct Identities {
+    mailmap: gix::mailmap::Snapshot,
+    /// Lowercased name or email to canonical name.
+    aliases: HashMap<String, String>,
+}
+
+impl Identities {
+    fn new(mailmap: gix::mailmap::Snapshot, identities: &HashMap<String, Vec<String>>) -> Self {
+        let mut aliases = HashMap::new();
+        for (canonical, names) in identities {
+            aliases.insert(canonical.to_lowercase(), canonical.clone());
+            for name in names {
+                aliases.insert(name.to_lowercase(), canonical.clone());
+            }
+        }
+        Self { mailmap, aliases }
+    }
+
+    fn canonical(&self, name: &str, email: &str) -> String {
+        let signature = gix::actor::SignatureRef {
+            name: name.into(),
+            email: email.into(),
+            time: "",
+        };
+        let resolved = self.mailmap.resolve_cow(signature);
+        let (mapped_name, mapped_email) = (resolved.name.to_string(), resolved.email.to_string());
+
+        let alias = [mapped_email.as_str(), mapped_name.as_str(), email, name]
+            .into_iter()
+            .find_map(|alias| self.aliases.get(&alias.to_lowercase()))
+            .cloned();
+        alias.unwrap_or(mapped_name)
+    }
+}
+
 pub struct EngineerBlame {
     repo: Mutex<gix::Repository>,
     repo_path: PathBuf,
@@ -99,12 +252,16 @@ pub struct EngineerBlame {
     min_violations: u64,
     blame_cache: Arc<Mutex<HashMap<String, Arc<Vec<BlameLine>>>>>,
     boundary_oid: Mutex<Option<gix::hash::ObjectId>>,
+    identities: Identities,
+    grouping: Grouping,
+    track_commits: bool,
 }
 
 #[derive(Clone)]
 struct BlameLine {
     author: String,
     time: i64,
+    commit: gix::hash::ObjectId,
 }
 
 impl EngineerBlame {
@@ -113,6 +270,9 @@ impl EngineerBlame {
         config: &BlameConfig,
     ) -> Result<Self, String> {
         let repo = gix::open(repo_path).map_err(|e| format!("Cannot open git repo: {e}"))?;
+        let identities = Identities::new(repo.open_mailmap(), &config.identities);
+        let workdir = repo.workdir().unwrap_or(repo_path).to_path_buf();
+        let grouping = Grouping::new(config.group_by, workdir)?;
 
         let since_ts = config.since.as_ref().and_then(|s| {
             parse_relative_date(s).map(|dt| dt.timestamp())
@@ -133,6 +293,9 @@ impl EngineerBlame {
             min_violations: config.min_violations,
             blame_cache: Arc::new(Mutex::new(HashMap::new())),
             boundary_oid: Mutex::new(None),
+            identities,
+            grouping,
+            track_commits: config.track_commits,
         })
     }
 
@@ -143,11 +306,7 @@ impl EngineerBlame {
         let path = Path::new(normalized)
             .strip_prefix(&workdir)
             .unwrap_or(Path::new(normalized));
-        let commit = repo.find_commit(boundary_oid).ok()?;
-        let tree = commit.tree().ok()?;
-        let entry = tree.lookup_entry_by_path(path).ok()??;
-        let blob = entry.object().ok()?.try_into_blob().ok()?;
-        std::str::from_utf8(&blob.data).ok().map(String::from)
+        crate::git::content_at(&repo, boundary_oid, path)
     }
 
     /// Walk the revwalk once to find the boundary commit (first commit ≤ since_ts),
@@ -159,19 +318,9 @@ impl EngineerBlame {
         let head_commit = repo.find_commit(head_id).ok()?;
         let head_tree = head_commit.tree().ok()?;
 
-        let walk = repo.rev_walk([head_id])
-            .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
-            .all().ok()?;
-
-        let boundary_oid = 'found: {
-            for info in walk {
-                let info = info.ok()?;
-                if info.commit_time() <= since_ts {
-                    break 'found info.id;
-                }
-            }
-            return None;
-        };
+        let boundary_oid = walk_commits(&repo)?
+            .find(|(_, time)| *time <= since_ts)
+            .map(|(id, _)| id)?;
 
         {
             let mut cached = self.boundary_oid.lock().ok()?;
@@ -280,6 +429,8 @@ impl EngineerBlame {
 
         let cache = Arc::clone(&self.blame_cache);
         let exclude_authors = &self.exclude_authors;
+        let identities = &self.identities;
+        let grouping = &self.grouping;
         let since_ts = self.since_ts;
         let until_ts = self.until_ts;
         let repo_path = self.repo_path.clone();
@@ -308,7 +459,9 @@ impl EngineerBlame {
 
                     for (file_path, violations) in &chunk {
                         for violation in *violations {
-                            let blame_lines = match get_blame_lines(&cache, &gix_repo, file_path, &wd) {
+                            let blame_lines = match get_blame_lines(
+                                &cache, &gix_repo, identities, file_path, &wd,
+                            ) {
                                 Ok(l) => l,
                                 Err(_) => continue,
                             };
@@ -343,7 +496,10 @@ impl EngineerBlame {
                                 continue;
                             }
 
-                            res.lock().unwrap().push((author, violation.rule.clone()));
+                            let mut res = res.lock().unwrap();
+                            for key in grouping.keys(file_path, &author) {
+                                res.push((key, violation.rule.clone()));
+                            }
                         }
                         if let Some(ref b) = bp {
                             b.inc(1);
@@ -418,6 +574,9 @@ impl EngineerBlame {
         let report_mutex: Mutex<EngineerReport> = Mutex::new(HashMap::new());
         let cache = Arc::clone(&self.blame_cache);
         let exclude_authors = self.exclude_authors.clone();
+        let identities = &self.identities;
+        let grouping = &self.grouping;
+        let track_commits = self.track_commits;
         let since_ts = self.since_ts;
         let until_ts = self.until_ts;
         let repo_path = self.repo_path.clone();
@@ -469,18 +628,38 @@ impl EngineerBlame {
                         for key in introduced_keys {
                             if let Some(violation) = current_by_key.get(key) {
                                 let author = get_majority_author(
-                                    &cache, &gix_repo, file_path, &workdir,
-                                    since_ts, until_ts,
-                                    violation.start_line, violation.end_line,
+                                    &cache,
+                                    &gix_repo,
+                                    identities,
+                                    file_path,
+                                    &workdir,
+                                    since_ts,
+                                    until_ts,
+                                    violation.start_line,
+                                    violation.end_line,
                                 );
-                                if let Some(ref author) = author {
+                                if let Some((ref author, commit)) = author {
                                     if is_author_excluded(author, "", &ex) {
                                         continue;
                                     }
-                                    let entry = local_report.entry(author.clone()).or_default();
-                                    entry.total_introduced += 1;
-                                    let rule_entry = entry.rules.entry(violation.rule.clone()).or_default();
-                                    rule_entry.introduced += 1;
+                                    for key in grouping.keys(file_path, author) {
+                                        let entry = local_report.entry(key).or_default();
+                                        entry.total_introduced += 1;
+                                        let rule_entry =
+                                            entry.rules.entry(violation.rule.clone()).or_default();
+                                        rule_entry.introduced += 1;
+                                        if track_commits {
+                                            entry.add_commit_violation(
+                                                &commit.to_string(),
+                                                CommitViolation {
+                                                    rule: violation.rule.clone(),
+                                                    file: file_path.clone(),
+                                                    line: violation.start_line,
+                                                    fixed: false,
+                                                },
+                                            );
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -488,18 +667,38 @@ impl EngineerBlame {
                         for key in fixed_keys {
                             if let Some(violation) = old_by_key.get(key) {
                                 let author = get_majority_author(
-                                    &cache, &gix_repo, file_path, &workdir,
-                                    since_ts, until_ts,
-                                    violation.start_line, violation.end_line,
+                                    &cache,
+                                    &gix_repo,
+                                    identities,
+                                    file_path,
+                                    &workdir,
+                                    since_ts,
+                                    until_ts,
+                                    violation.start_line,
+                                    violation.end_line,
                                 );
-                                if let Some(ref author) = author {
+                                if let Some((ref author, commit)) = author {
                                     if is_author_excluded(author, "", &ex) {
                                         continue;
                                     }
-                                    let entry = local_report.entry(author.clone()).or_default();
-                                    entry.total_fixed += 1;
-                                    let rule_entry = entry.rules.entry(violation.rule.clone()).or_default();
-                                    rule_entry.fixed += 1;
+                                    for key in grouping.keys(file_path, author) {
+                                        let entry = local_report.entry(key).or_default();
+                                        entry.total_fixed += 1;
+                                        let rule_entry =
+                                            entry.rules.entry(violation.rule.clone()).or_default();
+                                        rule_entry.fixed += 1;
+                                        if track_commits {
+                                            entry.add_commit_violation(
+                                                &commit.to_string(),
+                                                CommitViolation {
+                                                    rule: violation.rule.clone(),
+                                                    file: file_path.clone(),
+                                                    line: violation.start_line,
+                                                    fixed: true,
+                                                },
+                                            );
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -514,6 +713,11 @@ impl EngineerBlame {
                                 re.fixed += change.fixed;
                                 re.introduced += change.introduced;
                             }
+                            for commit in local_entry.commits {
+                                for violation in commit.violations {
+                                    entry.add_commit_violation(&commit.sha, violation);
+                                }
+                            }
                         }
 
                         if let Some(ref b) = bp {
@@ -525,12 +729,45 @@ impl EngineerBlame {
         });
 
         *report = report_mutex.into_inner().unwrap();
+
+        if self.track_commits {
+            self.describe_commits(report);
+        }
+    }
+
+    /// Fill in commit messages and order each entry's commits by how many
+    /// violations they changed.
+    fn describe_commits(&self, report: &mut EngineerReport) {
+        let repo = self.repo.lock().unwrap();
+        let mut messages: HashMap<String, String> = HashMap::new();
+        for entry in report.values_mut() {
+            for commit in &mut entry.commits {
+                let message = messages.entry(commit.sha.clone()).or_insert_with(|| {
+                    gix::hash::ObjectId::from_hex(commit.sha.as_bytes())
+                        .ok()
+                        .and_then(|id| repo.find_commit(id).ok())
+                        .and_then(|c| c.message_raw().ok().map(|m| m.to_string()))
+                        .and_then(|m| m.lines().next().map(|l| l.trim().to_string()))
+                        .unwrap_or_default()
+                });
+                commit.message = message.clone();
+                commit
+                    .violations
+                    .sort_by(|a, b| (&a.file, a.line, &a.rule).cmp(&(&b.file, b.line, &b.rule)));
+            }
+            entry.commits.sort_by(|a, b| {
+                (b.fixed + b.introduced)
+                    .cmp(&(a.fixed + a.introduced))
+                    .then_with(|| a.sha.cmp(&b.sha))
+            });
+        }
     }
 }
 
 fn get_blame_lines(
     cache: &Mutex<HashMap<String, Arc<Vec<BlameLine>>>>,
     gix_repo: &gix::Repository,
+    identities: &Identities,
     rel_path: &str,
     workdir: &Path,
 ) -> Result<Arc<Vec<BlameLine>>, String> {
@@ -558,19 +795,19 @@ fn get_blame_lines(
     for entry in outcome.entries {
         let commit = gix_repo.find_commit(entry.commit_id)
             .map_err(|e| format!("Cannot find commit {}: {e}", entry.commit_id))?;
-        let (name, _, time) = match commit.author() {
+        let (name, time) = match commit.author() {
             Ok(sig) => (
-                sig.name.to_string(),
-                sig.email.to_string(),
+                identities.canonical(&sig.name.to_string(), &sig.email.to_string()),
                 sig.time().ok().map(|t| t.seconds).unwrap_or(0),
             ),
-            Err(_) => ("unknown".to_string(), String::new(), 0),
+            Err(_) => ("unknown".to_string(), 0),
         };
         let count = entry.len.get() as usize;
         for _ in 0..count {
             lines.push(BlameLine {
                 author: name.clone(),
                 time,
+                commit: entry.commit_id,
             });
         }
     }
@@ -585,19 +822,21 @@ fn get_blame_lines(
 fn get_majority_author(
     cache: &Mutex<HashMap<String, Arc<Vec<BlameLine>>>>,
     gix_repo: &gix::Repository,
+    identities: &Identities,
     file_path: &str,
     workdir: &Path,
     since_ts: Option<i64>,
     until_ts: Option<i64>,
     start_line: usize,
     end_line: usize,
-) -> Option<String> {
-    let blame_lines = match get_blame_lines(cache, gix_repo, file_path, workdir) {
+) -> Option<(String, gix::hash::ObjectId)> {
+    let blame_lines = match get_blame_lines(cache, gix_repo, identities, file_path, workdir) {
         Ok(lines) => lines,
         Err(_) => return None,
     };
 
     let mut counts: HashMap<String, u64> = HashMap::new();
+    let mut in_window: Vec<&BlameLine> = Vec::new();
     let start = start_line.saturating_sub(1);
     let end = end_line.min(blame_lines.len());
 
@@ -614,10 +853,42 @@ fn get_majority_author(
         };
         if time_ok {
             *counts.entry(bl.author.clone()).or_insert(0) += 1;
+            in_window.push(bl);
         }
     }
 
-    counts.into_iter().max_by_key(|&(_, c)| c).map(|(a, _)| a)
+    let author = counts.into_iter().max_by_key(|&(_, c)| c).map(|(a, _)| a)?;
+
+    // The author's commit that touched most of the lines, the newest on a tie.
+    let mut commits: HashMap<gix::hash::ObjectId, (u64, i64)> = HashMap::new();
+    for bl in in_window.iter().filter(|bl| bl.author == author) {
+        let (count, time) = commits.entry(bl.commit).or_insert((0, bl.time));
+        *count += 1;
+        *time = (*time).max(bl.time);
+    }
+    let commit = commits
+        .into_iter()
+        .max_by_key(|&(id, rank)| (rank, id))
+        .map(|(id, _)| id)?;
+
+    Some((author, commit))
+}
+
+/// Commits reachable from HEAD as `(id, commit time)`, newest first. Shared by
+/// the `--since` boundary lookup and `--history`.
+pub(crate) fn walk_commits(
+    repo: &gix::Repository,
+) -> Option<impl Iterator<Item = (gix::hash::ObjectId, i64)> + '_> {
+    let head_id = repo.head_id().ok()?;
+    let walk = repo
+        .rev_walk([head_id])
+        .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+        .all()
+        .ok()?;
+    Some(
+        walk.map_while(Result::ok)
+            .map(|info| (info.id, info.commit_time())),
+    )
 }
 
 fn make_bar(total: usize, format: &Format) -> Option<ProgressBar> {
@@ -755,6 +1026,9 @@ mod tests {
             start_column: 1,
             end_line: 12,
             end_column: 5,
+            symbol: None,
+            baseline_state: None,
+            inline_suppressed: false,
         };
         let key = violation_key(&v);
         assert_eq!(key, "E001:10:Something wrong at 5");
@@ -806,6 +1080,181 @@ mod tests {
         assert_eq!(deserialized["Alice"].total_introduced, 5);
     }
 
+    #[test]
+    fn test_identities_apply_mailmap_then_config() {
+        let mailmap =
+            gix::mailmap::Snapshot::from_bytes(b"Jane Doe <jane@example.com> <jd@laptop.local>\n");
+        let mut config = HashMap::new();
+        config.insert(
+            "Jane Doe".to_string(),
+            vec!["JDOE".to_string(), "jane@old.example.com".to_string()],
+        );
+        let identities = Identities::new(mailmap, &config);
+
+        assert_eq!(identities.canonical("jd", "jd@laptop.local"), "Jane Doe");
+        assert_eq!(
+            identities.canonical("jdoe", "jdoe@ci.example.com"),
+            "Jane Doe"
+        );
+        assert_eq!(
+            identities.canonical("Janie", "Jane@Old.example.com"),
+            "Jane Doe"
+        );
+        assert_eq!(identities.canonical("Bob", "bob@example.com"), "Bob");
+    }
+
+    #[test]
+    fn test_blame_lines_use_canonical_authors() {
+        use crate::git::tests::{git, temp_repo};
+
+        let dir = temp_repo("mailmap");
+        std::fs::write(dir.join("src/A.php"), "<?php\n$a = 1;\n").unwrap();
+        git(
+            &dir,
+            &[
+                "-c",
+                "user.name=jd",
+                "-c",
+                "user.email=jd@laptop.local",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+        );
+        git(&dir, &["add", "."]);
+        git(
+            &dir,
+            &[
+                "-c",
+                "user.name=jd",
+                "-c",
+                "user.email=jd@laptop.local",
+                "commit",
+                "-q",
+                "-m",
+                "one",
+            ],
+        );
+        std::fs::write(dir.join("src/A.php"), "<?php\n$a = 1;\n$b = 2;\n").unwrap();
+        std::fs::write(
+            dir.join(".mailmap"),
+            "Jane Doe <jane@example.com>\nJane Doe <jane@example.com> <jd@laptop.local>\n",
+        )
+        .unwrap();
+        git(&dir, &["add", "."]);
+        git(
+            &dir,
+            &[
+                "-c",
+                "user.name=Jane",
+                "-c",
+                "user.email=jane@example.com",
+                "commit",
+                "-q",
+                "-m",
+                "two",
+            ],
+        );
+
+        let engineer = EngineerBlame::new(&dir, &BlameConfig::default()).unwrap();
+        let repo = gix::open(&dir).unwrap();
+        let lines = get_blame_lines(
+            &engineer.blame_cache,
+            &repo,
+            &engineer.identities,
+            "src/A.php",
+            &dir,
+        );
+        std::fs::remove_dir_all(&dir).ok();
+
+        let authors: Vec<String> = lines.unwrap().iter().map(|l| l.author.clone()).collect();
+        assert_eq!(authors, vec!["Jane Doe", "Jane Doe", "Jane Doe"]);
+    }
+
+    #[test]
+    fn test_majority_author_reports_the_commit_that_changed_the_lines() {
+        use crate::git::tests::{git, temp_repo};
+
+        let dir = temp_repo("majority_commit");
+        std::fs::write(dir.join("src/A.php"), "<?php\n$a = 1;\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(
+            &dir,
+            &["-c", "user.name=Alice", "commit", "-q", "-m", "one"],
+        );
+        std::fs::write(dir.join("src/A.php"), "<?php\n$a = 1;\n$b = 2;\n$c = 3;\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["-c", "user.name=Bob", "commit", "-q", "-m", "two"]);
+
+        let engineer = EngineerBlame::new(&dir, &BlameConfig::default()).unwrap();
+        let repo = gix::open(&dir).unwrap();
+        let head = repo.head_id().unwrap().detach();
+        let first = repo
+            .find_commit(head)
+            .unwrap()
+            .parent_ids()
+            .next()
+            .unwrap()
+            .detach();
+        let majority = |start, end| {
+            get_majority_author(
+                &engineer.blame_cache,
+                &repo,
+                &engineer.identities,
+                "src/A.php",
+                &dir,
+                None,
+                None,
+                start,
+                end,
+            )
+        };
+        let (bob, alice) = (majority(2, 4), majority(1, 2));
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(bob, Some(("Bob".to_string(), head)));
+        assert_eq!(alice, Some(("Alice".to_string(), first)));
+    }
+
+    #[test]
+    fn test_grouping_keys_by_team_owner_and_directory() {
+        let dir = std::env::temp_dir().join(format!("phanalist_grouping_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src/Billing")).unwrap();
+        std::fs::write(
+            dir.join("CODEOWNERS"),
+            "* @acme/platform\n/src/Billing/ @acme/payments @jane\n/vendor/\n",
+        )
+        .unwrap();
+        let file = dir.join("src/Billing/Invoice.php").display().to_string();
+        let unowned = dir.join("vendor/lib.php").display().to_string();
+
+        let team = Grouping::new(GroupBy::Team, dir.clone()).unwrap();
+        let owner = Grouping::new(GroupBy::Owner, dir.clone()).unwrap();
+        let directory = Grouping::new(GroupBy::Directory, dir.clone()).unwrap();
+        let engineer = Grouping::new(GroupBy::Engineer, dir.clone()).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(team.keys(&file, "Alice"), vec!["@acme/payments"]);
+        assert_eq!(team.keys(&unowned, "Alice"), vec!["(unowned)"]);
+        assert_eq!(owner.keys(&file, "Alice"), vec!["@acme/payments", "@jane"]);
+        assert_eq!(directory.keys(&file, "Alice"), vec!["src/Billing"]);
+        assert_eq!(engineer.keys(&file, "Alice"), vec!["Alice"]);
+        assert!(Grouping::new(
+            GroupBy::Team,
+            std::env::temp_dir().join("phanalist_no_such_repo")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_group_by_parse() {
+        assert_eq!("team".parse(), Ok(GroupBy::Team));
+        assert_eq!("Directory".parse(), Ok(GroupBy::Directory));
+        assert!("person".parse::<GroupBy>().is_err());
+    }
+
     #[test]
     fn test_engineer_entry_default() {
         let entry = EngineerEntry::default();
@@ -1,27 +1,162 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+};
 
 use mago_allocator::prelude::Arena;
 use mago_database::file::FileId;
-use mago_span::Span;
-use mago_syntax::cst::{Program, Statement};
+use mago_span::{HasSpan, Span};
+use mago_syntax::cst::{ClassLikeMember, Program, Sequence, Statement, Use, UseItem, UseItems};
 use serde::{Deserialize, Serialize};
 
+use crate::project_index::ProjectIndex;
+use crate::results::Violation;
+
+/// Comment marker silencing violations, optionally followed by rule codes.
+const IGNORE_MARKER: &str = "@phanalist-ignore";
+
 /// A PHP source file paired with its parsed AST.
 ///
-/// The AST is allocated inside an external arena; the same arena
-/// is reused across every file in a scan and freed in one go when the scan ends,
-/// instead of doing per-file heap allocations.
+/// The AST is allocated inside an external arena instead of doing per-file
+/// heap allocations. A scan resets its arena after indexing each file and
+/// between batches of validated files, so a `File` lives no longer than its batch.
 #[derive(Debug, Clone)]
 pub struct File<'arena> {
     pub path: PathBuf,
     pub lines: Vec<String>,
     pub line_starts: Vec<u32>,
-    pub namespace: Option<String>,
-    pub class_name: Option<String>,
+    /// Every class, interface, trait, enum and function declared in the file.
+    pub symbols: Vec<Symbol>,
+    /// Class imports of the global code, followed by those of each namespace.
+    pub name_scopes: Vec<NameScope>,
+    /// Index of the project the file is analysed in; see [`File::project`].
+    pub project: Option<Arc<ProjectIndex>>,
     pub reference_counter: RC,
     pub ast: Option<&'arena Program<'arena>>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SymbolKind {
+    Class,
+    Interface,
+    Trait,
+    Enum,
+    Function,
+}
+
+/// A top-level declaration, with the namespace it was declared in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub span: Span,
+}
+
+impl Symbol {
+    pub fn fully_qualified_name(&self) -> String {
+        match &self.namespace {
+            Some(ns) => format!("{ns}\\{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    pub fn is_class_like(&self) -> bool {
+        self.kind != SymbolKind::Function
+    }
+}
+
+/// Rules silenced by a `@phanalist-ignore` comment: on the line after the
+/// comment when it stands on its own line, else on the line it ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineSuppression {
+    pub line: usize,
+    /// Rule codes listed after the marker; empty for every rule.
+    pub rules: Vec<String>,
+}
+
+impl InlineSuppression {
+    pub fn covers(&self, violation: &Violation) -> bool {
+        violation.start_line == self.line
+            && (self.rules.is_empty() || self.rules.contains(&violation.rule))
+    }
+}
+
+/// The namespace and `use` imports that class names are resolved against in
+/// one part of a file.
+#[derive(Debug, Clone)]
+pub struct NameScope {
+    pub namespace: Option<String>,
+    pub span: Span,
+    /// Fully qualified class names by lowercase alias.
+    pub imports: HashMap<String, String>,
+}
+
+impl NameScope {
+    /// Fully qualified name, without leading `\`, of a class referenced as `name`.
+    ///
+    /// Follows PHP: `\Foo` is already qualified, `namespace\Foo` is relative to
+    /// the current namespace, a first segment matching an import (case
+    /// insensitive) is replaced by it, and anything else is prefixed with the
+    /// namespace. Built-in types, `self`, `static` and `parent` are unchanged.
+    pub fn resolve_class_name(&self, name: &str) -> String {
+        if let Some(name) = name.strip_prefix('\\') {
+            return name.to_string();
+        }
+        if is_builtin_type(name) {
+            return name.to_string();
+        }
+
+        let (first, rest) = match name.split_once('\\') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (name, None),
+        };
+        match rest {
+            Some(rest) if first.eq_ignore_ascii_case("namespace") => self.qualify(rest),
+            _ => match self.imports.get(&first.to_ascii_lowercase()) {
+                Some(import) => match rest {
+                    Some(rest) => format!("{import}\\{rest}"),
+                    None => import.clone(),
+                },
+                None => self.qualify(name),
+            },
+        }
+    }
+
+    fn qualify(&self, name: &str) -> String {
+        match &self.namespace {
+            Some(ns) => format!("{ns}\\{name}"),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// Type names that never refer to a user-defined class: PHP's reserved type
+/// keywords. Aliases such as `integer` or `boolean` are ordinary class names.
+pub fn is_builtin_type(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "array"
+            | "bool"
+            | "callable"
+            | "false"
+            | "float"
+            | "int"
+            | "iterable"
+            | "mixed"
+            | "never"
+            | "null"
+            | "object"
+            | "string"
+            | "true"
+            | "void"
+            | "self"
+            | "static"
+            | "parent"
+    )
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RC {
     pub methods: HashMap<String, Method>,
@@ -54,24 +189,107 @@ impl<'arena> File<'arena> {
         let line_starts = compute_line_starts(&content);
         let lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
-        let (namespace, class_name) = extract_namespace_and_class(program);
+        let mut symbols = Vec::new();
+        collect_symbols(&program.statements, None, &mut symbols);
+        let name_scopes = collect_name_scopes(program);
 
         Self {
             path,
             lines,
             line_starts,
-            namespace,
-            class_name,
+            symbols,
+            name_scopes,
+            project: None,
             reference_counter: RC::new(),
             ast: Some(program),
         }
     }
 
-    pub fn get_fully_qualified_name(&self) -> Option<String> {
-        match &self.namespace {
-            Some(n) => self.class_name.as_ref().map(|s| format!("{}\\{}", n, s)),
-            None => self.class_name.clone(),
+    /// Every class-like of the project, for cross-file rules. Empty until the
+    /// analyser attaches the index built for the scan.
+    pub fn project(&self) -> &ProjectIndex {
+        static EMPTY: LazyLock<ProjectIndex> = LazyLock::new(ProjectIndex::default);
+        self.project.as_deref().unwrap_or(&EMPTY)
+    }
+
+    /// The class-like or function declared around `offset`.
+    pub fn symbol_at(&self, offset: u32) -> Option<&Symbol> {
+        self.symbols
+            .iter()
+            .find(|symbol| offset >= symbol.span.start.offset && offset <= symbol.span.end.offset)
+    }
+
+    /// The innermost name scope around `offset`: its namespace, or the global code.
+    pub fn name_scope(&self, offset: u32) -> Option<&NameScope> {
+        self.name_scopes
+            .iter()
+            .rev()
+            .find(|scope| offset >= scope.span.start.offset && offset <= scope.span.end.offset)
+    }
+
+    /// Fully qualified name of a class referenced as `name` at `offset`, after
+    /// applying the namespace and `use` imports in effect there.
+    pub fn resolve_class_name(&self, name: &str, offset: u32) -> String {
+        match self.name_scope(offset) {
+            Some(scope) => scope.resolve_class_name(name),
+            None => name.trim_start_matches('\\').to_string(),
+        }
+    }
+
+    /// Fully qualified name of the innermost class-like, method or function
+    /// declared around `offset`: `App\Foo`, `App\Foo::bar` or `App\helper`.
+    pub fn enclosing_symbol(&self, offset: u32) -> Option<String> {
+        let symbol = self.symbol_at(offset)?;
+        let name = symbol.fully_qualified_name();
+        if !symbol.is_class_like() {
+            return Some(name);
         }
+
+        let method = self
+            .ast
+            .and_then(|program| find_enclosing_method(&program.statements, offset));
+        Some(match method {
+            Some(method) => format!("{name}::{method}"),
+            None => name,
+        })
+    }
+
+    /// The `@phanalist-ignore` comments of the file.
+    pub fn inline_suppressions(&self) -> Vec<InlineSuppression> {
+        let Some(program) = self.ast else {
+            return Vec::new();
+        };
+
+        program
+            .trivia
+            .iter()
+            .filter(|trivia| trivia.kind.is_comment())
+            .filter_map(|comment| {
+                let text = String::from_utf8_lossy(comment.value);
+                let (_, codes) = text.split_once(IGNORE_MARKER)?;
+                let rules = codes
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|word| !word.is_empty())
+                    .take_while(|word| {
+                        word.len() > 1
+                            && word.starts_with('E')
+                            && word[1..].bytes().all(|b| b.is_ascii_digit())
+                    })
+                    .map(str::to_string)
+                    .collect();
+
+                let start = comment.span.start.offset;
+                let line_start = self.line_starts[self.line_number(start).saturating_sub(1)];
+                let own_line = program.source_text[line_start as usize..start as usize]
+                    .iter()
+                    .all(u8::is_ascii_whitespace);
+                let line = self.line_number(comment.span.end.offset);
+                Some(InlineSuppression {
+                    line: if own_line { line + 1 } else { line },
+                    rules,
+                })
+            })
+            .collect()
     }
 
     /// 1-based line number for a byte offset within this file's content.
@@ -129,35 +347,241 @@ fn compute_line_starts(content: &str) -> Vec<u32> {
     starts
 }
 
-fn extract_namespace_and_class<'arena>(
-    program: &Program<'arena>,
-) -> (Option<String>, Option<String>) {
-    let mut namespace = None;
-    let mut class_name = None;
+fn collect_symbols(
+    statements: &Sequence<'_, Statement<'_>>,
+    namespace: Option<&str>,
+    symbols: &mut Vec<Symbol>,
+) {
+    for statement in statements.iter() {
+        let (kind, name) = match statement {
+            Statement::Namespace(ns) => {
+                let name = ns
+                    .name
+                    .as_ref()
+                    .map(|n| String::from_utf8_lossy(n.value()).to_string());
+                collect_symbols(ns.statements(), name.as_deref(), symbols);
+                continue;
+            }
+            Statement::Class(class) => (SymbolKind::Class, class.name.value),
+            Statement::Interface(interface) => (SymbolKind::Interface, interface.name.value),
+            Statement::Trait(t) => (SymbolKind::Trait, t.name.value),
+            Statement::Enum(e) => (SymbolKind::Enum, e.name.value),
+            Statement::Function(function) => (SymbolKind::Function, function.name.value),
+            _ => continue,
+        };
+        symbols.push(Symbol {
+            kind,
+            name: String::from_utf8_lossy(name).into_owned(),
+            namespace: namespace.map(str::to_string),
+            span: statement.span(),
+        });
+    }
+}
+
+fn collect_name_scopes(program: &Program<'_>) -> Vec<NameScope> {
+    let mut global = NameScope {
+        namespace: None,
+        span: program.span(),
+        imports: HashMap::new(),
+    };
+    let mut scopes = Vec::new();
 
     for statement in program.statements.iter() {
         match statement {
             Statement::Namespace(ns) => {
-                if let Some(name) = ns.name.as_ref() {
-                    namespace = Some(String::from_utf8_lossy(name.value()).to_string());
-                }
-                for s in ns.statements().iter() {
-                    if let Statement::Class(class) = s {
-                        class_name = Some(String::from_utf8_lossy(class.name.value).into_owned());
-                        break;
+                let mut scope = NameScope {
+                    namespace: ns
+                        .name
+                        .as_ref()
+                        .map(|name| String::from_utf8_lossy(name.value()).into_owned()),
+                    span: ns.span(),
+                    imports: HashMap::new(),
+                };
+                for statement in ns.statements().iter() {
+                    if let Statement::Use(r#use) = statement {
+                        collect_imports(r#use, &mut scope.imports);
                     }
                 }
+                scopes.push(scope);
             }
-            Statement::Class(class) => {
-                class_name = Some(String::from_utf8_lossy(class.name.value).to_string());
-            }
+            Statement::Use(r#use) => collect_imports(r#use, &mut global.imports),
             _ => {}
         }
+    }
 
-        if class_name.is_some() {
-            break;
+    scopes.insert(0, global);
+    scopes
+}
+
+/// Class imports of a `use` statement; `use function` and `use const` are skipped.
+fn collect_imports(r#use: &Use<'_>, imports: &mut HashMap<String, String>) {
+    let mut import = |prefix: Option<&[u8]>, item: &UseItem<'_>| {
+        let name = String::from_utf8_lossy(item.name.value());
+        let name = name.trim_start_matches('\\');
+        let fqn = match prefix {
+            Some(prefix) => format!(
+                "{}\\{name}",
+                String::from_utf8_lossy(prefix).trim_start_matches('\\')
+            ),
+            None => name.to_string(),
+        };
+        let alias = match &item.alias {
+            Some(alias) => String::from_utf8_lossy(alias.identifier.value).into_owned(),
+            None => fqn.rsplit('\\').next().unwrap_or(&fqn).to_string(),
+        };
+        imports.insert(alias.to_ascii_lowercase(), fqn);
+    };
+
+    match &r#use.items {
+        UseItems::Sequence(sequence) => {
+            for item in sequence.items.iter() {
+                import(None, item);
+            }
+        }
+        UseItems::MixedList(list) => {
+            for item in list.items.iter().filter(|item| item.r#type.is_none()) {
+                import(Some(list.namespace.value()), &item.item);
+            }
         }
+        UseItems::TypedSequence(_) | UseItems::TypedList(_) => {}
     }
+}
+
+/// Name of the method declared around `offset` in any class-like.
+fn find_enclosing_method(statements: &Sequence<'_, Statement<'_>>, offset: u32) -> Option<String> {
+    let contains = |span: Span| offset >= span.start.offset && offset <= span.end.offset;
+
+    statements
+        .iter()
+        .filter(|statement| contains(statement.span()))
+        .find_map(|statement| {
+            let members = match statement {
+                Statement::Namespace(ns) => return find_enclosing_method(ns.statements(), offset),
+                Statement::Class(class) => &class.members,
+                Statement::Interface(interface) => &interface.members,
+                Statement::Trait(t) => &t.members,
+                Statement::Enum(e) => &e.members,
+                _ => return None,
+            };
+            members.iter().find_map(|member| match member {
+                ClassLikeMember::Method(method) if contains(method.span()) => {
+                    Some(String::from_utf8_lossy(method.name.value).into_owned())
+                }
+                _ => None,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use mago_allocator::prelude::LocalArena;
 
-    (namespace, class_name)
+    use super::*;
+
+    fn offset_of(content: &str, needle: &str) -> u32 {
+        content.find(needle).unwrap() as u32
+    }
+
+    #[test]
+    fn inline_suppressions_cover_their_line_or_the_next() {
+        let content = "<?php\n// @phanalist-ignore E0011\n$a = @fopen('a');\n$b = @fopen('b'); # @phanalist-ignore\n/* @phanalist-ignore E0009, E0011 legacy */\n$c = 1;\n";
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("Log.php"), content.to_string());
+
+        let suppression = |line, rules: &[&str]| InlineSuppression {
+            line,
+            rules: rules.iter().map(|rule| rule.to_string()).collect(),
+        };
+        assert_eq!(
+            file.inline_suppressions(),
+            vec![
+                suppression(3, &["E0011"]),
+                suppression(4, &[]),
+                suppression(6, &["E0009", "E0011"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn enclosing_symbol_resolves_methods_and_functions() {
+        let content = "<?php\nnamespace App;\n\nclass Foo {\n    public function bar() { return 1; }\n}\n\nfunction helper() { return 2; }\n";
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("Foo.php"), content.to_string());
+
+        assert_eq!(
+            file.enclosing_symbol(offset_of(content, "return 1")),
+            Some("App\\Foo::bar".to_string())
+        );
+        assert_eq!(
+            file.enclosing_symbol(offset_of(content, "class Foo")),
+            Some("App\\Foo".to_string())
+        );
+        assert_eq!(
+            file.enclosing_symbol(offset_of(content, "return 2")),
+            Some("App\\helper".to_string())
+        );
+        assert_eq!(file.enclosing_symbol(0), None);
+    }
+
+    #[test]
+    fn symbols_cover_every_declaration_and_namespace() {
+        let content = "<?php\nnamespace App\\Model;\n\ninterface HasId {}\ntrait Timestamps {}\nenum Status { case Active; }\nclass User implements HasId {}\nclass Admin extends User {}\n\nnamespace App\\Support;\n\nfunction helper() {}\n";
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("Model.php"), content.to_string());
+
+        let symbols: Vec<(SymbolKind, String)> = file
+            .symbols
+            .iter()
+            .map(|s| (s.kind, s.fully_qualified_name()))
+            .collect();
+        assert_eq!(
+            symbols,
+            vec![
+                (SymbolKind::Interface, "App\\Model\\HasId".to_string()),
+                (SymbolKind::Trait, "App\\Model\\Timestamps".to_string()),
+                (SymbolKind::Enum, "App\\Model\\Status".to_string()),
+                (SymbolKind::Class, "App\\Model\\User".to_string()),
+                (SymbolKind::Class, "App\\Model\\Admin".to_string()),
+                (SymbolKind::Function, "App\\Support\\helper".to_string()),
+            ]
+        );
+        assert_eq!(
+            file.symbol_at(offset_of(content, "helper"))
+                .map(Symbol::fully_qualified_name),
+            Some("App\\Support\\helper".to_string())
+        );
+    }
+
+    #[test]
+    fn class_names_resolve_against_imports_and_namespace() {
+        let content = "<?php\nnamespace App\\Billing;\n\nuse App\\Model\\{Invoice, Customer as Client};\nuse App\\Mail;\nuse function App\\Support\\format;\n\nclass Mailer {}\n";
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("Mailer.php"), content.to_string());
+        let offset = offset_of(content, "class Mailer");
+
+        let resolve = |name| file.resolve_class_name(name, offset);
+        assert_eq!(resolve("Invoice"), "App\\Model\\Invoice");
+        assert_eq!(resolve("client"), "App\\Model\\Customer");
+        assert_eq!(resolve("Mail\\Mailer"), "App\\Mail\\Mailer");
+        assert_eq!(resolve("\\App\\Mail\\Mailer"), "App\\Mail\\Mailer");
+        assert_eq!(resolve("namespace\\Gateway"), "App\\Billing\\Gateway");
+        assert_eq!(resolve("Format"), "App\\Billing\\Format");
+        assert_eq!(resolve("Mailer"), "App\\Billing\\Mailer");
+        assert_eq!(resolve("self"), "self");
+        assert_eq!(resolve("int"), "int");
+    }
+
+    #[test]
+    fn type_aliases_resolve_as_class_names() {
+        let content = "<?php\nnamespace App;\n\nuse Foo\\Boolean;\n\nclass Flag {}\n";
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("Flag.php"), content.to_string());
+        let offset = offset_of(content, "class Flag");
+
+        let resolve = |name| file.resolve_class_name(name, offset);
+        assert_eq!(resolve("Integer"), "App\\Integer");
+        assert_eq!(resolve("Boolean"), "Foo\\Boolean");
+        assert_eq!(resolve("bool"), "bool");
+        assert_eq!(resolve("object"), "object");
+    }
 }
@@ -0,0 +1,607 @@
+//! Git helpers for pull-request and pre-commit scans: work out which PHP files
+//! and lines a branch touched, or which files are staged, so only those are
+//! validated and reported while the whole project is still indexed. Also reads
+//! whole revisions straight from the object database for `--rev`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::Sender;
+
+use gix::diff::blob::{Diff, InternedInput};
+use gix::hash::ObjectId;
+use gix::status::UntrackedFiles;
+
+use crate::results::Results;
+
+/// The set of PHP files that differ between a git ref and the working tree,
+/// or that are staged for the next commit.
+///
+/// Paths are stored absolute and canonicalized, so scanned paths of any shape
+/// (`./src/Foo.php`, `src/Foo.php`, absolute) can be looked up.
+#[derive(Debug, Clone, Default)]
+pub struct ChangedFiles {
+    paths: HashSet<PathBuf>,
+    /// Blob contents from the index, only filled for staged files. They are
+    /// analysed instead of the working tree copy.
+    staged: HashMap<PathBuf, String>,
+}
+
+impl ChangedFiles {
+    /// Collect the PHP files changed between `reference` and the working tree of
+    /// the repository containing `src`: committed changes since the ref, staged
+    /// and unstaged edits, and untracked files.
+    pub fn since(src: &Path, reference: &str) -> Result<Self, String> {
+        let (repo, workdir) = open_repo(src)?;
+        let tree_id = resolve_tree(&repo, reference)?;
+
+        // Comparing the index against the ref's tree (instead of HEAD's) covers
+        // everything committed since the ref; the index-worktree half adds
+        // local edits and untracked files.
+        let status = repo
+            .status(gix::progress::Discard)
+            .map_err(|e| format!("Cannot compute git status: {e}"))?
+            .untracked_files(UntrackedFiles::Files)
+            .head_tree(tree_id)
+            .into_iter(Vec::new())
+            .map_err(|e| format!("Cannot compute git status: {e}"))?;
+
+        let mut paths = HashSet::new();
+        for item in status {
+            let item = item.map_err(|e| format!("Cannot compute git status: {e}"))?;
+            let location = item.location().to_string();
+            if location.ends_with(".php") {
+                paths.insert(workdir.join(location));
+            }
+        }
+
+        Ok(Self {
+            paths,
+            staged: HashMap::new(),
+        })
+    }
+
+    /// Collect the PHP files whose index entry differs from `HEAD` in the
+    /// repository containing `src`, along with their staged content. Conflicted
+    /// entries are skipped.
+    pub fn staged(src: &Path) -> Result<Self, String> {
+        let (repo, workdir) = open_repo(src)?;
+        let head_tree = repo
+            .head_tree_id_or_empty()
+            .map_err(|e| format!("Cannot read the tree of HEAD: {e}"))?;
+        let head_tree = repo
+            .find_tree(head_tree)
+            .map_err(|e| format!("Cannot read the tree of HEAD: {e}"))?;
+        let index = repo
+            .index_or_empty()
+            .map_err(|e| format!("Cannot read the git index: {e}"))?;
+
+        let mut paths = HashSet::new();
+        let mut staged = HashMap::new();
+        for entry in index.entries() {
+            let location = entry.path(&index).to_string();
+            if !location.ends_with(".php") || entry.stage_raw() != 0 {
+                continue;
+            }
+            let at_head = head_tree
+                .lookup_entry_by_path(&location)
+                .map_err(|e| format!("Cannot read {location} at HEAD: {e}"))?;
+            if at_head.is_some_and(|e| e.object_id() == entry.id) {
+                continue;
+            }
+
+            let blob = repo
+                .find_blob(entry.id)
+                .map_err(|e| format!("Cannot read the staged {location}: {e}"))?;
+            let path = workdir.join(&location);
+            staged.insert(
+                path.clone(),
+                String::from_utf8_lossy(&blob.data).into_owned(),
+            );
+            paths.insert(path);
+        }
+
+        Ok(Self { paths, staged })
+    }
+
+    /// The staged content to analyse for `path` instead of the file on disk.
+    pub fn staged_content(&self, path: &Path) -> Option<&str> {
+        if self.staged.is_empty() {
+            return None;
+        }
+        let path = path.canonicalize().ok()?;
+        self.staged.get(&path).map(String::as_str)
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        match path.canonicalize() {
+            Ok(path) => self.paths.contains(&path),
+            Err(_) => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+/// Lines added or modified since a git ref, used to report only the
+/// violations a pull request touches.
+pub struct ChangedLines {
+    repo: gix::Repository,
+    workdir: PathBuf,
+    tree_id: ObjectId,
+}
+
+impl ChangedLines {
+    pub fn since(src: &Path, reference: &str) -> Result<Self, String> {
+        let (repo, workdir) = open_repo(src)?;
+        let tree_id = resolve_tree(&repo, reference)?;
+
+        Ok(Self {
+            repo,
+            workdir,
+            tree_id,
+        })
+    }
+
+    /// 1-based, inclusive line ranges of `content` that were added or modified
+    /// compared to `path` at the ref. A file missing at the ref is new as a
+    /// whole; pure deletions leave no range behind.
+    pub fn hunks(&self, path: &Path, content: &str) -> Vec<(usize, usize)> {
+        let before = self.content_at_ref(path).unwrap_or_default();
+        line_diff(&before, content)
+            .into_iter()
+            .filter(|after| !after.is_empty())
+            .map(|after| (after.start as usize + 1, after.end as usize))
+            .collect()
+    }
+
+    /// Keep only the violations whose `start_line..=end_line` overlaps an added
+    /// or modified hunk. Class-level violations span the whole class, so they
+    /// stay when any line of the class changed.
+    pub fn filter(&self, results: &mut Results) {
+        for (path, violations) in results.files.iter_mut() {
+            if violations.is_empty() {
+                continue;
+            }
+            let content = fs::read_to_string(path).unwrap_or_default();
+            let hunks = self.hunks(Path::new(path), &content);
+            violations.retain(|violation| {
+                hunks.iter().any(|(start, end)| {
+                    violation.start_line <= *end && violation.end_line >= *start
+                })
+            });
+        }
+        results.recount_codes();
+    }
+
+    fn content_at_ref(&self, path: &Path) -> Option<String> {
+        let path = path.canonicalize().ok()?;
+        let relative = path.strip_prefix(&self.workdir).ok()?;
+        content_at(&self.repo, self.tree_id, relative)
+    }
+}
+
+/// A commit whose tree is scanned straight from the object database, leaving
+/// the working copy untouched.
+pub struct Revision {
+    // Thread-safe so `Analyse` stays shareable across the blame threads.
+    repo: gix::ThreadSafeRepository,
+    workdir: PathBuf,
+    tree_id: ObjectId,
+}
+
+impl Revision {
+    /// Resolve `rev` (any commit-ish: tag, branch, sha, `HEAD~3`) in the
+    /// repository containing `src`, or the current directory when `src` does
+    /// not exist in the working copy.
+    pub fn resolve(src: &Path, rev: &str) -> Result<Self, String> {
+        let start = if src.exists() { src } else { Path::new(".") };
+        let (repo, workdir) = open_repo(start)?;
+        let tree_id = resolve_tree(&repo, rev)?;
+
+        Ok(Self {
+            repo: repo.into_sync(),
+            workdir,
+            tree_id,
+        })
+    }
+
+    /// `path` relative to the repository root.
+    pub fn repo_path(&self, path: &Path) -> Option<PathBuf> {
+        repo_relative(&self.workdir, path)
+    }
+
+    /// The same repository at another commit (or tree).
+    pub fn at(&self, commit: ObjectId) -> Result<Self, String> {
+        let tree_id = self
+            .repo
+            .to_thread_local()
+            .find_object(commit)
+            .map_err(|e| format!("Cannot read {commit}: {e}"))?
+            .peel_to_tree()
+            .map_err(|e| format!("Cannot read the tree of {commit}: {e}"))?
+            .id;
+
+        Ok(Self {
+            repo: self.repo.clone(),
+            workdir: self.workdir.clone(),
+            tree_id,
+        })
+    }
+
+    /// Content of the blob `id`, decoded lossily as UTF-8.
+    pub fn blob(&self, id: ObjectId) -> Result<String, String> {
+        let repo = self.repo.to_thread_local();
+        let blob = repo
+            .find_blob(id)
+            .map_err(|e| format!("Cannot read blob {id}: {e}"))?;
+        Ok(String::from_utf8_lossy(&blob.data).into_owned())
+    }
+
+    /// Content of the file at `path`, shaped like the paths `send_files` sends.
+    pub fn content(&self, path: &Path) -> Result<String, String> {
+        let relative = self
+            .repo_path(path)
+            .ok_or_else(|| format!("{} is outside of the git repository", path.display()))?;
+        let repo = self.repo.to_thread_local();
+        content_at(&repo, self.tree_id, &relative)
+            .ok_or_else(|| format!("Cannot read {} at the revision", path.display()))
+    }
+
+    /// Send every PHP file below `src` at this revision through `sender`, the
+    /// way `scan_folder` does for files on disk. Paths keep the shape of `src`
+    /// (`./src/Foo.php`) so output and `exclude_paths` look the same as for a
+    /// working copy scan.
+    pub fn send_files(
+        &self,
+        src: &Path,
+        sender: Sender<(String, PathBuf)>,
+        exclude_paths: &[String],
+    ) -> Result<(), String> {
+        for (relative, id) in self.php_files(src)? {
+            if !exclude_paths.is_empty()
+                && crate::paths::is_excluded(
+                    &crate::paths::normalize_relative(&relative),
+                    exclude_paths,
+                )
+            {
+                continue;
+            }
+            let content = self.blob(id)?;
+            if sender.send((content, relative)).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// The PHP blobs below `src` at this revision, as `src`-shaped paths with
+    /// their blob ids.
+    pub fn php_files(&self, src: &Path) -> Result<Vec<(PathBuf, ObjectId)>, String> {
+        let repo = self.repo.to_thread_local();
+        let prefix = repo_relative(&self.workdir, src)
+            .ok_or_else(|| format!("{} is outside of the git repository", src.display()))?;
+        let tree = repo
+            .find_tree(self.tree_id)
+            .map_err(|e| format!("Cannot read the revision tree: {e}"))?;
+        let mut recorder = gix::traverse::tree::Recorder::default();
+        tree.traverse()
+            .breadthfirst(&mut recorder)
+            .map_err(|e| format!("Cannot read the revision tree: {e}"))?;
+
+        let mut files = Vec::new();
+        for entry in recorder.records {
+            let location = entry.filepath.to_string();
+            if !entry.mode.is_blob() || !location.ends_with(".php") {
+                continue;
+            }
+            if let Ok(inner) = Path::new(&location).strip_prefix(&prefix) {
+                files.push((src.join(inner), entry.oid));
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Content of the repository-relative `path` in the tree of `commit` (a commit
+/// or tree id), or `None` when it does not exist there.
+pub fn content_at(repo: &gix::Repository, commit: ObjectId, path: &Path) -> Option<String> {
+    let tree = repo.find_object(commit).ok()?.peel_to_tree().ok()?;
+    let entry = tree.lookup_entry_by_path(path).ok()??;
+    let blob = entry.object().ok()?.try_into_blob().ok()?;
+    Some(String::from_utf8_lossy(&blob.data).into_owned())
+}
+
+/// The changed line ranges of `after` against `before`, 0-based and
+/// half-open. A pure deletion is the empty range where the lines used to be.
+pub fn line_diff(before: &str, after: &str) -> Vec<std::ops::Range<u32>> {
+    let input = InternedInput::new(before, after);
+    let mut diff = Diff::compute(gix::diff::blob::Algorithm::Histogram, &input);
+    diff.postprocess_lines(&input);
+    diff.hunks().map(|hunk| hunk.after).collect()
+}
+
+/// `path` relative to the repository `workdir`, resolved lexically so it also
+/// works for directories that only exist in another revision.
+pub(crate) fn repo_relative(workdir: &Path, path: &Path) -> Option<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        let cwd = std::env::current_dir().ok()?;
+        cwd.canonicalize().unwrap_or(cwd).join(path)
+    };
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized.strip_prefix(workdir).ok().map(Path::to_path_buf)
+}
+
+/// Discover the repository containing `src`, with its canonicalized workdir.
+fn open_repo(src: &Path) -> Result<(gix::Repository, PathBuf), String> {
+    let repo = gix::discover(src)
+        .map_err(|e| format!("No git repository found for {}: {e}", src.display()))?;
+    let workdir = repo
+        .workdir()
+        .ok_or("Repository has no working directory (bare repo).")?
+        .to_path_buf();
+    let workdir = workdir.canonicalize().unwrap_or(workdir);
+    Ok((repo, workdir))
+}
+
+fn resolve_tree(repo: &gix::Repository, reference: &str) -> Result<ObjectId, String> {
+    Ok(repo
+        .rev_parse_single(reference)
+        .map_err(|e| format!("Cannot resolve git ref {reference}: {e}"))?
+        .object()
+        .map_err(|e| format!("Cannot read {reference}: {e}"))?
+        .peel_to_tree()
+        .map_err(|e| format!("Cannot read the tree of {reference}: {e}"))?
+        .id)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::results::{Message, Violation};
+    use std::process::Command;
+
+    pub(crate) fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args([
+                "-c",
+                "commit.gpgsign=false",
+                "-c",
+                "init.defaultBranch=main",
+            ])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("git is installed");
+        assert!(status.status.success(), "git {args:?} failed: {status:?}");
+    }
+
+    pub(crate) fn temp_repo(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("phanalist_{name}_{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        git(&dir, &["init", "-q"]);
+        dir
+    }
+
+    #[test]
+    fn changed_since_includes_committed_modified_and_untracked_files() {
+        let dir = temp_repo("changed_since");
+        for name in ["Committed", "Modified", "Untouched", "Staged"] {
+            fs::write(dir.join(format!("src/{name}.php")), "<?php\n").unwrap();
+        }
+        fs::write(dir.join("src/notes.txt"), "x").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "base"]);
+        git(&dir, &["tag", "base"]);
+
+        fs::write(dir.join("src/Committed.php"), "<?php\nclass A {}\n").unwrap();
+        git(&dir, &["commit", "-q", "-am", "change"]);
+        fs::write(dir.join("src/Modified.php"), "<?php\nclass B {}\n").unwrap();
+        fs::write(dir.join("src/Staged.php"), "<?php\nclass C {}\n").unwrap();
+        git(&dir, &["add", "src/Staged.php"]);
+        fs::write(dir.join("src/New.php"), "<?php\n").unwrap();
+        fs::write(dir.join("src/notes.txt"), "y").unwrap();
+
+        let changed = ChangedFiles::since(&dir, "base").unwrap();
+        let src = dir.join("src");
+        let result = (
+            changed.contains(&src.join("Committed.php")),
+            changed.contains(&src.join("Modified.php")),
+            changed.contains(&src.join("Staged.php")),
+            changed.contains(&src.join("New.php")),
+            changed.contains(&src.join("Untouched.php")),
+            changed.len(),
+        );
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(result, (true, true, true, true, false, 4));
+    }
+
+    #[test]
+    fn changed_since_rejects_unknown_ref() {
+        let dir = temp_repo("changed_since_ref");
+        fs::write(dir.join("src/A.php"), "<?php\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "base"]);
+
+        let result = ChangedFiles::since(&dir, "does-not-exist");
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(result.unwrap_err().contains("does-not-exist"));
+    }
+
+    #[test]
+    fn staged_reads_index_content_instead_of_worktree() {
+        let dir = temp_repo("staged");
+        fs::write(dir.join("src/Staged.php"), "<?php\n").unwrap();
+        fs::write(dir.join("src/Unstaged.php"), "<?php\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "base"]);
+
+        fs::write(dir.join("src/Staged.php"), "<?php\nclass Staged {}\n").unwrap();
+        git(&dir, &["add", "src/Staged.php"]);
+        fs::write(dir.join("src/Staged.php"), "<?php\nclass NotYetStaged {}\n").unwrap();
+        fs::write(dir.join("src/Unstaged.php"), "<?php\nclass B {}\n").unwrap();
+        fs::write(dir.join("src/Added.php"), "<?php\nclass C {}\n").unwrap();
+        git(&dir, &["add", "src/Added.php"]);
+
+        let staged = ChangedFiles::staged(&dir).unwrap();
+        let src = dir.join("src");
+        let result = (
+            staged
+                .staged_content(&src.join("Staged.php"))
+                .map(String::from),
+            staged.contains(&src.join("Added.php")),
+            staged.contains(&src.join("Unstaged.php")),
+            staged.staged_content(&src.join("Unstaged.php")).is_none(),
+        );
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(
+            result,
+            (
+                Some("<?php\nclass Staged {}\n".to_string()),
+                true,
+                false,
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn revision_reads_php_files_from_the_commit_tree() {
+        let dir = temp_repo("revision");
+        fs::create_dir_all(dir.join("src/Sub")).unwrap();
+        fs::write(dir.join("src/A.php"), "<?php\nclass A {}\n").unwrap();
+        fs::write(dir.join("src/Sub/B.php"), "<?php\nclass B {}\n").unwrap();
+        fs::write(dir.join("src/readme.txt"), "x").unwrap();
+        fs::write(dir.join("Outside.php"), "<?php\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "base"]);
+        git(&dir, &["tag", "v1"]);
+        fs::write(dir.join("src/A.php"), "<?php\nclass Changed {}\n").unwrap();
+        fs::remove_file(dir.join("src/Sub/B.php")).unwrap();
+
+        let revision = Revision::resolve(&dir, "v1").unwrap();
+        let (send, recv) = std::sync::mpsc::channel();
+        revision.send_files(&dir.join("src"), send, &[]).unwrap();
+        let files: Vec<(String, PathBuf)> = recv.iter().collect();
+        let reread = revision.content(&dir.join("src/A.php"));
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(
+            files,
+            vec![
+                ("<?php\nclass A {}\n".to_string(), dir.join("src/A.php")),
+                ("<?php\nclass B {}\n".to_string(), dir.join("src/Sub/B.php")),
+            ]
+        );
+        assert_eq!(reread, Ok("<?php\nclass A {}\n".to_string()));
+    }
+
+    #[test]
+    fn repo_relative_resolves_lexically() {
+        let workdir = Path::new("/repo");
+        assert_eq!(
+            repo_relative(workdir, Path::new("/repo/./src/../lib")),
+            Some(PathBuf::from("lib"))
+        );
+        assert_eq!(repo_relative(workdir, Path::new("/elsewhere")), None);
+    }
+
+    fn violation(start_line: usize, end_line: usize) -> Violation {
+        Violation {
+            rule: "E0001".to_string(),
+            line: String::new(),
+            message: Message::new("E0001:test", "test"),
+            start_line,
+            start_column: 0,
+            end_line,
+            end_column: 0,
+            symbol: None,
+            baseline_state: None,
+            inline_suppressed: false,
+        }
+    }
+
+    #[test]
+    fn changed_lines_keeps_violations_on_modified_hunks() {
+        let dir = temp_repo("changed_lines");
+        let file = dir.join("src/Foo.php");
+        fs::write(
+            &file,
+            "<?php\nclass Foo\n{\n    a();\n    b();\n    c();\n}\n",
+        )
+        .unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "base"]);
+        fs::write(
+            &file,
+            "<?php\nclass Foo\n{\n    a();\n    changed();\n    c();\n    added();\n}\n",
+        )
+        .unwrap();
+
+        let lines = ChangedLines::since(&dir, "HEAD").unwrap();
+        let hunks = lines.hunks(&file, &fs::read_to_string(&file).unwrap());
+
+        let mut results = Results::default();
+        let path = file.display().to_string();
+        results.files.insert(
+            path.clone(),
+            vec![
+                violation(4, 4),
+                violation(5, 5),
+                violation(6, 6),
+                violation(7, 7),
+                violation(2, 8),
+            ],
+        );
+        lines.filter(&mut results);
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(hunks, vec![(5, 5), (7, 7)]);
+        let kept: Vec<(usize, usize)> = results.files[&path]
+            .iter()
+            .map(|v| (v.start_line, v.end_line))
+            .collect();
+        assert_eq!(kept, vec![(5, 5), (7, 7), (2, 8)]);
+        assert_eq!(results.codes_count.get("E0001"), Some(&3));
+    }
+
+    #[test]
+    fn changed_lines_treats_new_files_as_changed() {
+        let dir = temp_repo("changed_lines_new");
+        fs::write(dir.join("src/A.php"), "<?php\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "base"]);
+        let file = dir.join("src/New.php");
+        fs::write(&file, "<?php\nclass A {}\n").unwrap();
+
+        let hunks = ChangedLines::since(&dir, "HEAD")
+            .unwrap()
+            .hunks(&file, "<?php\nclass A {}\n");
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(hunks, vec![(1, 2)]);
+    }
+}
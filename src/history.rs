@@ -0,0 +1,386 @@
+//! Quality trend over git history: sample commits at an interval, analyse each
+//! snapshot straight from the object database and collect per-rule violation
+//! counts as a time series.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::DateTime;
+use gix::hash::ObjectId;
+use indicatif::ProgressBar;
+use mago_allocator::prelude::LocalArena;
+use serde::{Deserialize, Serialize};
+
+use crate::analyse::{Analyse, BATCH_BYTES};
+use crate::config::Config;
+use crate::engineer::walk_commits;
+use crate::file::File;
+use crate::git::Revision;
+use crate::project_index::ProjectIndex;
+use crate::results::Violation;
+
+const DAY: i64 = 24 * 60 * 60;
+
+/// How far apart sampled commits are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interval {
+    Weekly,
+    /// 30 days, matching how `--since "3 months"` is parsed.
+    Monthly,
+    /// Every N commits.
+    Commits(usize),
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "weekly" | "week" => Ok(Interval::Weekly),
+            "monthly" | "month" => Ok(Interval::Monthly),
+            other => match other.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(Interval::Commits(n)),
+                _ => Err(format!(
+                    "Invalid history interval \"{input}\": use weekly, monthly or a number of commits"
+                )),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interval::Weekly => write!(f, "weekly"),
+            Interval::Monthly => write!(f, "monthly"),
+            Interval::Commits(n) => write!(f, "every {n} commits"),
+        }
+    }
+}
+
+/// Violation counts of one sampled commit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Snapshot {
+    pub commit: String,
+    /// Commit date, `YYYY-MM-DD`.
+    pub date: String,
+    pub files: usize,
+    pub total: i64,
+    pub codes_count: BTreeMap<String, i64>,
+}
+
+/// The time series, oldest snapshot first.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct History {
+    pub interval: String,
+    pub snapshots: Vec<Snapshot>,
+}
+
+/// Pick the commits to analyse from `commits` (newest first, as yielded by
+/// `walk_commits`). Time intervals take the newest commit at or before each
+/// sample point, counting back from `until` (or the newest commit); commits
+/// older than `since` are not sampled. Returned oldest first.
+pub fn select_commits(
+    commits: impl IntoIterator<Item = (ObjectId, i64)>,
+    interval: Interval,
+    since_ts: Option<i64>,
+    until_ts: Option<i64>,
+) -> Vec<(ObjectId, i64)> {
+    let step = match interval {
+        Interval::Weekly => 7 * DAY,
+        Interval::Monthly => 30 * DAY,
+        Interval::Commits(_) => 0,
+    };
+
+    let mut selected = Vec::new();
+    let mut next_point = until_ts;
+    let mut seen = 0;
+    for (id, time) in commits {
+        if until_ts.is_some_and(|until| time > until) {
+            continue;
+        }
+        if since_ts.is_some_and(|since| time < since) {
+            break;
+        }
+
+        match interval {
+            Interval::Commits(n) => {
+                if seen % n == 0 {
+                    selected.push((id, time));
+                }
+                seen += 1;
+            }
+            Interval::Weekly | Interval::Monthly => {
+                let point = *next_point.get_or_insert(time);
+                if time <= point {
+                    selected.push((id, time));
+                    // Skip sample points this commit already stands for.
+                    let mut point = point - step;
+                    while time <= point {
+                        point -= step;
+                    }
+                    next_point = Some(point);
+                }
+            }
+        }
+    }
+
+    selected.reverse();
+    selected
+}
+
+/// Per-rule counts of a file for the rules whose results only depend on the
+/// file itself, keyed by path and blob so unchanged files are not re-validated.
+type LocalCache = HashMap<(PathBuf, ObjectId), HashMap<String, i64>>;
+
+/// Sample the history of the repository containing `src_paths[0]` and analyse
+/// every selected commit.
+pub fn collect(
+    src_paths: &[String],
+    config: &Config,
+    interval: Interval,
+    since_ts: Option<i64>,
+    until_ts: Option<i64>,
+    bar: Option<&ProgressBar>,
+) -> Result<History, String> {
+    let revision = Revision::resolve(Path::new(&src_paths[0]), "HEAD")?;
+    let commits = {
+        let repo = gix::discover(Path::new(&src_paths[0]))
+            .map_err(|e| format!("Cannot open git repo: {e}"))?;
+        let walk = walk_commits(&repo).ok_or("Cannot walk the git history")?;
+        select_commits(walk, interval, since_ts, until_ts)
+    };
+    if let Some(bar) = bar {
+        bar.set_length(commits.len() as u64);
+    }
+
+    let mut history = History {
+        interval: interval.to_string(),
+        snapshots: Vec::with_capacity(commits.len()),
+    };
+    let mut cache = LocalCache::new();
+    let mut previous_files: Vec<(PathBuf, ObjectId)> = Vec::new();
+
+    for (id, time) in commits {
+        let snapshot = revision.at(id)?;
+        let mut files = Vec::new();
+        for src in src_paths {
+            files.extend(
+                snapshot
+                    .php_files(Path::new(src))?
+                    .into_iter()
+                    .filter(|(path, _)| {
+                        !crate::paths::is_excluded(
+                            &crate::paths::normalize_relative(path),
+                            &config.exclude_paths,
+                        )
+                    }),
+            );
+        }
+
+        let codes_count = match history.snapshots.last() {
+            // Nothing below the sources changed: the counts are the same.
+            Some(last) if files == previous_files => last.codes_count.clone(),
+            _ => analyse_snapshot(&snapshot, &files, config, &mut cache)?,
+        };
+
+        history.snapshots.push(Snapshot {
+            commit: id.to_hex_with_len(10).to_string(),
+            date: DateTime::from_timestamp(time, 0)
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            files: files.len(),
+            total: codes_count.values().sum(),
+            codes_count,
+        });
+        previous_files = files;
+
+        if let Some(bar) = bar {
+            bar.inc(1);
+        }
+    }
+
+    Ok(history)
+}
+
+/// Analyse one snapshot. Every file is parsed so cross-file rules index the
+/// whole project, but file-local rules only run on blobs not seen before.
+fn analyse_snapshot(
+    snapshot: &Revision,
+    files: &[(PathBuf, ObjectId)],
+    config: &Config,
+    cache: &mut LocalCache,
+) -> Result<BTreeMap<String, i64>, String> {
+    // Fresh rules per snapshot: cross-file indexes must not mix revisions.
+    let (cross_rules, local_rules) = Analyse::new(config)
+        .rules
+        .into_iter()
+        .partition(|(_, rule)| rule.is_cross_file());
+    let cross = Analyse {
+        time_budget: config.time_budget.clone(),
+        ..Analyse::from_rules(cross_rules)
+    };
+    let local = Analyse {
+        time_budget: config.time_budget.clone(),
+        ..Analyse::from_rules(local_rules)
+    };
+
+    // Index first, then parse again a batch at a time, as `Analyse::scan` does,
+    // so memory is bounded by the batch size rather than the snapshot.
+    let mut arena = LocalArena::new();
+    let mut project = ProjectIndex::default();
+    for (path, id) in files {
+        let file = File::new(&arena, path.clone(), snapshot.blob(*id)?);
+        project.add_file(&file);
+        drop(file);
+        arena.reset();
+    }
+    cross.set_project(project);
+
+    let mut codes_count = BTreeMap::new();
+    let mut batch_bytes = 0;
+    for (path, id) in files {
+        let content = snapshot.blob(*id)?;
+        if batch_bytes >= BATCH_BYTES {
+            arena.reset();
+            batch_bytes = 0;
+        }
+        batch_bytes += content.len();
+
+        let mut file = File::new(&arena, path.clone(), content);
+        let key = (file.path.clone(), *id);
+        if !cache.contains_key(&key) {
+            let (violations, _) = local.analyse_file(&mut file, false);
+            cache.insert(key.clone(), count_by_rule(&violations));
+        }
+        for (rule, count) in &cache[&key] {
+            *codes_count.entry(rule.clone()).or_insert(0) += count;
+        }
+
+        // The local run attached its own, empty, index to the file.
+        file.project = None;
+        let (violations, _) = cross.analyse_file(&mut file, false);
+        for (rule, count) in count_by_rule(&violations) {
+            *codes_count.entry(rule).or_insert(0) += count;
+        }
+    }
+
+    Ok(codes_count)
+}
+
+fn count_by_rule(violations: &[Violation]) -> HashMap<String, i64> {
+    let mut counts = HashMap::new();
+    for violation in violations.iter().filter(|v| !v.is_suppressed()) {
+        *counts.entry(violation.rule.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::{git, temp_repo};
+    use std::fs;
+
+    fn commit(n: u8, time: i64) -> (ObjectId, i64) {
+        (ObjectId::from_bytes_or_panic(&[n; 20]), time)
+    }
+
+    #[test]
+    fn interval_parses_names_and_commit_counts() {
+        assert_eq!("weekly".parse(), Ok(Interval::Weekly));
+        assert_eq!("Monthly".parse(), Ok(Interval::Monthly));
+        assert_eq!("10".parse(), Ok(Interval::Commits(10)));
+        assert!("0".parse::<Interval>().is_err());
+        assert!("daily".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn select_commits_takes_newest_commit_per_week() {
+        // Newest first: two commits in the latest week, none in the week
+        // before, one older.
+        let commits = vec![
+            commit(1, 30 * DAY),
+            commit(2, 29 * DAY),
+            commit(3, 20 * DAY),
+            commit(4, 5 * DAY),
+        ];
+
+        let selected = select_commits(commits, Interval::Weekly, None, None);
+
+        let ids: Vec<u8> = selected.iter().map(|(id, _)| id.as_bytes()[0]).collect();
+        assert_eq!(ids, vec![4, 3, 1]);
+    }
+
+    #[test]
+    fn select_commits_respects_window_and_commit_steps() {
+        let commits: Vec<_> = (0..7u8)
+            .map(|n| commit(n + 1, (10 - n as i64) * DAY))
+            .collect();
+
+        let selected = select_commits(commits, Interval::Commits(2), Some(5 * DAY), Some(9 * DAY));
+
+        // Commit 1 is after `until`, commits older than day 5 are outside.
+        let ids: Vec<u8> = selected.iter().map(|(id, _)| id.as_bytes()[0]).collect();
+        assert_eq!(ids, vec![6, 4, 2]);
+    }
+
+    #[test]
+    fn collect_analyses_every_sampled_commit() {
+        let dir = temp_repo("history");
+        let class = "<?php\nclass A {\n    public function a() {\n        return 1;\n    }\n}\n";
+        fs::write(dir.join("src/A.php"), class).unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "one"]);
+        fs::write(dir.join("src/B.php"), class.replace("class A", "class B")).unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "two"]);
+        fs::write(dir.join("README.md"), "docs").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "three"]);
+
+        let config = Config {
+            enabled_rules: vec!["E0008".to_string()],
+            ..Default::default()
+        };
+        let src = dir.join("src").display().to_string();
+        let history = collect(&[src], &config, Interval::Commits(1), None, None, None);
+        fs::remove_dir_all(&dir).ok();
+
+        let totals: Vec<(usize, i64)> = history
+            .unwrap()
+            .snapshots
+            .iter()
+            .map(|s| (s.files, s.total))
+            .collect();
+        assert_eq!(totals, vec![(1, 1), (2, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn cross_file_rules_see_the_whole_snapshot() {
+        let dir = temp_repo("history-cross");
+        let parents =
+            "<?php\nclass A {}\nclass B extends A {}\nclass C extends B {}\nclass D extends C {}\n";
+        fs::write(dir.join("src/Parents.php"), parents).unwrap();
+        fs::write(
+            dir.join("src/F.php"),
+            "<?php\nclass E extends D {}\nclass F extends E {}\n",
+        )
+        .unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "one"]);
+
+        let config = Config {
+            enabled_rules: vec!["E0008".to_string(), "E0020".to_string()],
+            ..Default::default()
+        };
+        let src = dir.join("src").display().to_string();
+        let history = collect(&[src], &config, Interval::Commits(1), None, None, None);
+        fs::remove_dir_all(&dir).ok();
+
+        // F is five levels deep, which only shows with D's ancestors indexed.
+        let snapshots = history.unwrap().snapshots;
+        assert_eq!(snapshots[0].codes_count.get("E0020"), Some(&1));
+    }
+}
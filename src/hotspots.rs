@@ -0,0 +1,501 @@
+//! Hotspots: code that is both complex and often changed. The commits touching
+//! each file, class or method over a window are multiplied by a complexity
+//! metric of the current code, ranking the best candidates for refactoring.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use gix::hash::ObjectId;
+use indicatif::ProgressBar;
+use mago_allocator::prelude::LocalArena;
+use mago_span::HasSpan;
+use mago_syntax::cst::{ClassLikeMember, MethodBody, Sequence, Statement};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::engineer::walk_commits;
+use crate::file::File;
+use crate::git::{line_diff, Revision};
+use crate::rules::{e16, e24, e9};
+
+/// What a hotspot is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    File,
+    Class,
+    Method,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "file" => Ok(Level::File),
+            "class" => Ok(Level::Class),
+            "method" => Ok(Level::Method),
+            _ => Err(format!(
+                "Invalid hotspot level \"{input}\": use file, class or method"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::File => write!(f, "file"),
+            Level::Class => write!(f, "class"),
+            Level::Method => write!(f, "method"),
+        }
+    }
+}
+
+/// The complexity churn is multiplied by. Classes and files sum the values of
+/// their methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Cyclomatic complexity, as in E0009.
+    Cyclomatic,
+    /// Cognitive complexity, as in E0016.
+    Cognitive,
+    /// Lines of code per method, as in E0024.
+    Lines,
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "cyclomatic" | "e0009" => Ok(Metric::Cyclomatic),
+            "cognitive" | "e0016" => Ok(Metric::Cognitive),
+            "lines" | "loc" | "e0024" => Ok(Metric::Lines),
+            _ => Err(format!(
+                "Invalid hotspot metric \"{input}\": use cyclomatic, cognitive or lines"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Metric::Cyclomatic => write!(f, "cyclomatic complexity"),
+            Metric::Cognitive => write!(f, "cognitive complexity"),
+            Metric::Lines => write!(f, "lines of code"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Hotspot {
+    /// File path, class name or `Class::method`.
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    /// Commits in the window that changed it.
+    pub commits: u64,
+    pub cyclomatic: i64,
+    pub cognitive: i64,
+    pub lines: i64,
+    /// `commits` times the selected metric.
+    pub score: i64,
+}
+
+/// Hotspots ranked by score, highest first.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Hotspots {
+    pub level: String,
+    pub metric: String,
+    /// Commits in the window.
+    pub commits: usize,
+    pub hotspots: Vec<Hotspot>,
+}
+
+/// Churn is counted per repository path and symbol (empty for files).
+type Key = (String, String);
+
+/// Rank the code below `src_paths` at HEAD, counting the commits between
+/// `since_ts` and `until_ts`.
+pub fn collect(
+    src_paths: &[String],
+    config: &Config,
+    level: Level,
+    metric: Metric,
+    since_ts: Option<i64>,
+    until_ts: Option<i64>,
+    bar: Option<&ProgressBar>,
+) -> Result<Hotspots, String> {
+    let revision = Revision::resolve(Path::new(&src_paths[0]), "HEAD")?;
+    let mut units = Vec::new();
+    let mut tracked = HashSet::new();
+    for src in src_paths {
+        for (path, id) in revision.php_files(Path::new(src))? {
+            if crate::paths::is_excluded(
+                &crate::paths::normalize_relative(&path),
+                &config.exclude_paths,
+            ) {
+                continue;
+            }
+            let Some(repo_path) = revision.repo_path(&path) else {
+                continue;
+            };
+            let repo_path = repo_path.to_string_lossy().replace('\\', "/");
+            let arena = LocalArena::new();
+            let file = File::new(&arena, path, revision.blob(id)?);
+            units.extend(measure(&file, &repo_path, level));
+            tracked.insert(repo_path);
+        }
+    }
+
+    let repo = gix::discover(Path::new(&src_paths[0]))
+        .map_err(|e| format!("Cannot open git repo: {e}"))?;
+    let commits: Vec<ObjectId> = walk_commits(&repo)
+        .ok_or("Cannot walk the git history")?
+        .skip_while(|(_, time)| until_ts.is_some_and(|until| *time > until))
+        .take_while(|(_, time)| since_ts.is_none_or(|since| *time >= since))
+        .map(|(id, _)| id)
+        .collect();
+    if let Some(bar) = bar {
+        bar.set_length(commits.len() as u64);
+    }
+
+    let mut churn: HashMap<Key, u64> = HashMap::new();
+    for id in &commits {
+        for key in touched(&repo, *id, &tracked, level)? {
+            *churn.entry(key).or_insert(0) += 1;
+        }
+        if let Some(bar) = bar {
+            bar.inc(1);
+        }
+    }
+
+    let mut hotspots: Vec<Hotspot> = units
+        .into_iter()
+        .filter_map(|(key, mut hotspot)| {
+            hotspot.commits = churn.get(&key).copied()?;
+            let value = match metric {
+                Metric::Cyclomatic => hotspot.cyclomatic,
+                Metric::Cognitive => hotspot.cognitive,
+                Metric::Lines => hotspot.lines,
+            };
+            hotspot.score = hotspot.commits as i64 * value;
+            Some(hotspot)
+        })
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.commits.cmp(&a.commits))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(Hotspots {
+        level: level.to_string(),
+        metric: metric.to_string(),
+        commits: commits.len(),
+        hotspots,
+    })
+}
+
+/// The units of `file` at `level` with their metrics, churn still zero.
+fn measure(file: &File<'_>, repo_path: &str, level: Level) -> Vec<(Key, Hotspot)> {
+    let mut methods = Vec::new();
+    let mut classes = Vec::new();
+    if let Some(program) = file.ast {
+        collect_methods(file, &program.statements, None, &mut classes, &mut methods);
+    }
+
+    let display = file.path.display().to_string();
+    let unit = |symbol: &str, name: String, line: usize, metrics: (i64, i64, i64)| {
+        let key = (repo_path.to_string(), symbol.to_string());
+        let hotspot = Hotspot {
+            name,
+            file: display.clone(),
+            line,
+            cyclomatic: metrics.0,
+            cognitive: metrics.1,
+            lines: metrics.2,
+            ..Default::default()
+        };
+        (key, hotspot)
+    };
+    let sum = |prefix: Option<&str>| {
+        methods
+            .iter()
+            .filter(|(name, _, _)| prefix.is_none_or(|p| class_of(name) == p))
+            .fold((0, 0, 0), |acc, (_, _, m)| {
+                (acc.0 + m.0, acc.1 + m.1, acc.2 + m.2)
+            })
+    };
+
+    match level {
+        Level::File => vec![unit("", display.clone(), 1, sum(None))],
+        Level::Class => classes
+            .iter()
+            .map(|(name, line)| unit(name, name.clone(), *line, sum(Some(name))))
+            .collect(),
+        Level::Method => methods
+            .iter()
+            .map(|(name, line, metrics)| unit(name, name.clone(), *line, *metrics))
+            .collect(),
+    }
+}
+
+type MethodMetrics = (String, usize, (i64, i64, i64));
+
+/// Class-likes as `(name, line)` and their concrete methods as
+/// `(Class::method, line, (cyclomatic, cognitive, lines))`, named the way
+/// `File::enclosing_symbol` names them.
+fn collect_methods(
+    file: &File<'_>,
+    statements: &Sequence<'_, Statement<'_>>,
+    namespace: Option<&str>,
+    classes: &mut Vec<(String, usize)>,
+    methods: &mut Vec<MethodMetrics>,
+) {
+    for statement in statements.iter() {
+        let (name, members) = match statement {
+            Statement::Namespace(ns) => {
+                let name = ns
+                    .name
+                    .as_ref()
+                    .map(|n| String::from_utf8_lossy(n.value()).to_string());
+                collect_methods(file, ns.statements(), name.as_deref(), classes, methods);
+                continue;
+            }
+            Statement::Class(class) => (class.name.value, &class.members),
+            Statement::Interface(interface) => (interface.name.value, &interface.members),
+            Statement::Trait(t) => (t.name.value, &t.members),
+            Statement::Enum(e) => (e.name.value, &e.members),
+            _ => continue,
+        };
+
+        let name = String::from_utf8_lossy(name);
+        let class = match namespace {
+            Some(ns) => format!("{ns}\\{name}"),
+            None => name.into_owned(),
+        };
+        for member in members.iter() {
+            if let ClassLikeMember::Method(method) = member {
+                if let MethodBody::Concrete(block) = &method.body {
+                    let metrics = (
+                        1 + e9::calculate_complexity(&block.statements),
+                        e16::calculate_cognitive_complexity(&block.statements, 0),
+                        e24::body_loc(file, block) as i64,
+                    );
+                    let method_name = String::from_utf8_lossy(method.name.value);
+                    methods.push((
+                        format!("{class}::{method_name}"),
+                        file.line_number(method.span().start.offset),
+                        metrics,
+                    ));
+                }
+            }
+        }
+        classes.push((class, file.line_number(statement.span().start.offset)));
+    }
+}
+
+fn class_of(symbol: &str) -> &str {
+    symbol.split("::").next().unwrap_or(symbol)
+}
+
+/// The tracked units commit `id` changed, compared to its first parent.
+fn touched(
+    repo: &gix::Repository,
+    id: ObjectId,
+    tracked: &HashSet<String>,
+    level: Level,
+) -> Result<HashSet<Key>, String> {
+    let commit = repo
+        .find_commit(id)
+        .map_err(|e| format!("Cannot read commit {id}: {e}"))?;
+    let tree = commit
+        .tree()
+        .map_err(|e| format!("Cannot read the tree of {id}: {e}"))?;
+    let parent_tree = match commit.parent_ids().next() {
+        Some(parent) => Some(
+            repo.find_commit(parent)
+                .map_err(|e| format!("Cannot read the parent of {id}: {e}"))?
+                .tree()
+                .map_err(|e| format!("Cannot read the parent of {id}: {e}"))?,
+        ),
+        None => None,
+    };
+    let changes = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .map_err(|e| format!("Cannot diff commit {id}: {e}"))?;
+
+    let mut touched = HashSet::new();
+    for change in changes {
+        use gix::object::tree::diff::ChangeDetached;
+        let (location, previous, current) = match change {
+            ChangeDetached::Addition { location, id, .. } => (location, None, id),
+            ChangeDetached::Modification {
+                location,
+                previous_id,
+                id,
+                ..
+            } => (location, Some(previous_id), id),
+            ChangeDetached::Rewrite {
+                location,
+                source_id,
+                id,
+                ..
+            } => (location, Some(source_id), id),
+            ChangeDetached::Deletion { .. } => continue,
+        };
+        let location = location.to_string();
+        if !tracked.contains(&location) {
+            continue;
+        }
+        if level == Level::File {
+            touched.insert((location, String::new()));
+            continue;
+        }
+
+        let blob = |id: ObjectId| {
+            repo.find_blob(id)
+                .map(|b| String::from_utf8_lossy(&b.data).into_owned())
+                .map_err(|e| format!("Cannot read blob {id}: {e}"))
+        };
+        let before = match previous {
+            Some(previous) => blob(previous)?,
+            None => String::new(),
+        };
+        let content = blob(current)?;
+        let ranges = line_diff(&before, &content);
+        let arena = LocalArena::new();
+        let file = File::new(&arena, location.clone().into(), content);
+        for range in ranges {
+            // A deletion touches the line it happened after.
+            let lines = if range.is_empty() {
+                range.start.max(1)..=range.start.max(1)
+            } else {
+                range.start + 1..=range.end
+            };
+            for line in lines {
+                if let Some(symbol) = symbol_at_line(&file, line as usize) {
+                    let symbol = match level {
+                        Level::Class => class_of(&symbol).to_string(),
+                        _ => symbol,
+                    };
+                    touched.insert((location.clone(), symbol));
+                }
+            }
+        }
+    }
+    Ok(touched)
+}
+
+/// The class or method around the first code on 1-based `line`.
+fn symbol_at_line(file: &File<'_>, line: usize) -> Option<String> {
+    let start = *file.line_starts.get(line.checked_sub(1)?)?;
+    let indent = file
+        .lines
+        .get(line - 1)
+        .map(|l| l.len() - l.trim_start().len())
+        .unwrap_or(0);
+    file.enclosing_symbol(start + indent as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::{git, temp_repo};
+    use std::fs;
+
+    const CLASS: &str = "<?php\nnamespace App;\n\nclass A {\n    public function simple() {\n        return 1;\n    }\n\n    public function busy($a) {\n        if ($a) {\n            return 2;\n        }\n        return 3;\n    }\n}\n";
+
+    #[test]
+    fn level_and_metric_parse() {
+        assert_eq!("Method".parse(), Ok(Level::Method));
+        assert!("package".parse::<Level>().is_err());
+        assert_eq!("E0016".parse(), Ok(Metric::Cognitive));
+        assert_eq!("loc".parse(), Ok(Metric::Lines));
+        assert!("halstead".parse::<Metric>().is_err());
+    }
+
+    #[test]
+    fn measure_names_units_like_enclosing_symbol() {
+        let arena = LocalArena::new();
+        let file = File::new(&arena, "src/A.php".into(), CLASS.to_string());
+
+        let methods = measure(&file, "src/A.php", Level::Method);
+        let classes = measure(&file, "src/A.php", Level::Class);
+
+        let busy = &methods[1].1;
+        assert_eq!(methods[1].0 .1, "App\\A::busy");
+        assert_eq!(
+            (busy.cyclomatic, busy.cognitive, busy.lines, busy.line),
+            (2, 1, 4, 9)
+        );
+        assert_eq!(classes[0].1.name, "App\\A");
+        assert_eq!(classes[0].1.cyclomatic, 3);
+        assert_eq!(symbol_at_line(&file, 11), Some("App\\A::busy".to_string()));
+    }
+
+    #[test]
+    fn collect_ranks_methods_by_churn_times_complexity() {
+        let dir = temp_repo("hotspots");
+        fs::write(dir.join("src/A.php"), CLASS).unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "one"]);
+        fs::write(
+            dir.join("src/A.php"),
+            CLASS.replace("return 3;", "return 4;"),
+        )
+        .unwrap();
+        git(&dir, &["commit", "-q", "-am", "two"]);
+        fs::write(
+            dir.join("src/A.php"),
+            CLASS.replace("return 3;", "return 5;"),
+        )
+        .unwrap();
+        git(&dir, &["commit", "-q", "-am", "three"]);
+
+        let src = dir.join("src").display().to_string();
+        let config = Config::default();
+        let methods = collect(
+            std::slice::from_ref(&src),
+            &config,
+            Level::Method,
+            Metric::Cyclomatic,
+            None,
+            None,
+            None,
+        );
+        let files = collect(
+            &[src],
+            &config,
+            Level::File,
+            Metric::Lines,
+            None,
+            None,
+            None,
+        );
+        fs::remove_dir_all(&dir).ok();
+
+        let methods = methods.unwrap();
+        assert_eq!(methods.commits, 3);
+        let ranked: Vec<(&str, u64, i64)> = methods
+            .hotspots
+            .iter()
+            .map(|h| (h.name.as_str(), h.commits, h.score))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![("App\\A::busy", 3, 6), ("App\\A::simple", 1, 1)]
+        );
+
+        let files = files.unwrap();
+        assert_eq!(files.hotspots.len(), 1);
+        assert_eq!(
+            (files.hotspots[0].commits, files.hotspots[0].score),
+            (3, 3 * 5)
+        );
+    }
+}
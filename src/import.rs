@@ -0,0 +1,752 @@
+//! Convert PHPStan and Psalm baselines and ignore configuration into phanalist
+//! equivalents, to ease migrating a project that already froze its debt with
+//! one of those tools.
+//!
+//! Only issues with a phanalist counterpart can be carried over:
+//!
+//! | PHPStan identifier    | Psalm issue         | Rule  |
+//! |-----------------------|---------------------|-------|
+//! | `missingType.return`  | `MissingReturnType` | E0008 |
+//! | `method.unused`       | `UnusedMethod`      | E0013 |
+//!
+//! Counted per-file entries become baseline entries, globally suppressed issues
+//! become `disable_rules`, and excluded/ignored paths become `exclude_paths`.
+//! Everything else is reported back as unmapped.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::baseline::{split_dir_file, Baseline, BaselineEntry};
+use crate::paths::normalize_relative;
+use crate::rules;
+
+/// What a converted baseline or config maps to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    /// Version 1 (count-only) baseline: imported entries have no source line
+    /// to fingerprint.
+    pub baseline: Baseline,
+    pub exclude_paths: Vec<String>,
+    pub disable_rules: Vec<String>,
+    pub unmapped: Vec<Unmapped>,
+}
+
+/// An entry that has no phanalist equivalent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unmapped {
+    pub path: Option<String>,
+    pub description: String,
+    pub count: usize,
+}
+
+struct Mapping {
+    phpstan_identifier: &'static str,
+    /// Fallback for baselines written before PHPStan had identifiers: the
+    /// message must name a `Method` and contain this text.
+    phpstan_message: &'static str,
+    psalm_issue: &'static str,
+    rule: &'static str,
+    id: &'static str,
+    message: &'static str,
+}
+
+fn mappings() -> [Mapping; 2] {
+    [
+        Mapping {
+            phpstan_identifier: "missingType.return",
+            phpstan_message: "has no return type specified",
+            psalm_issue: "MissingReturnType",
+            rule: rules::e8::CODE,
+            id: "E0008:missing-return-type",
+            message:
+                "The method {name} has a return statement but it has no return type signature.",
+        },
+        Mapping {
+            phpstan_identifier: "method.unused",
+            phpstan_message: "is unused",
+            psalm_issue: "UnusedMethod",
+            rule: rules::e13::CODE,
+            id: "E0013:private-method-not-called",
+            message: "The private method {name} is not being called. ",
+        },
+    ]
+}
+
+impl Import {
+    /// Convert every file in `paths` (`.neon` for PHPStan, `.xml` for Psalm)
+    /// and merge the results.
+    pub fn from_files(paths: &[PathBuf]) -> Result<Import, String> {
+        let mut import = Import {
+            baseline: Baseline {
+                version: 1,
+                violations: vec![],
+            },
+            exclude_paths: vec![],
+            disable_rules: vec![],
+            unmapped: vec![],
+        };
+        for path in paths {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+            let base = path.parent().unwrap_or(Path::new(""));
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("neon") => {
+                    let value = parse_neon(&text)
+                        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+                    import.add_phpstan(&value, base);
+                }
+                Some("xml") => import.add_psalm(&text, base),
+                _ => {
+                    return Err(format!(
+                        "Unsupported file {}: expected a PHPStan .neon or Psalm .xml file",
+                        path.display()
+                    ))
+                }
+            }
+        }
+        import.finish();
+        Ok(import)
+    }
+
+    fn add_phpstan(&mut self, neon: &Value, base: &Path) {
+        let parameters = &neon["parameters"];
+
+        for pattern in list_of(&parameters["excludePaths"]) {
+            self.exclude_paths.push(resolve(base, &pattern));
+        }
+        for key in ["analyse", "analyseAndScan"] {
+            for pattern in list_of(&parameters["excludePaths"][key]) {
+                self.exclude_paths.push(resolve(base, &pattern));
+            }
+        }
+
+        let Some(ignore_errors) = parameters["ignoreErrors"].as_array() else {
+            return;
+        };
+        for item in ignore_errors {
+            let (messages, identifier) = match item {
+                Value::String(message) => (vec![message.clone()], String::new()),
+                _ => {
+                    let mut messages = list_of(&item["message"]);
+                    messages.extend(list_of(&item["messages"]));
+                    let identifier = item["identifier"].as_str().unwrap_or_default().to_string();
+                    (messages, identifier)
+                }
+            };
+            let mut paths = list_of(&item["path"]);
+            paths.extend(list_of(&item["paths"]));
+            let count = item["count"].as_u64().map(|c| c as usize);
+
+            let mapping = mappings().into_iter().find(|m| {
+                identifier == m.phpstan_identifier
+                    || (identifier.is_empty()
+                        && messages
+                            .iter()
+                            .any(|msg| msg.contains("Method ") && msg.contains(m.phpstan_message)))
+            });
+            let description = if identifier.is_empty() {
+                messages.join(", ")
+            } else {
+                format!("{identifier} {}", messages.join(", "))
+            };
+
+            match (mapping, paths.as_slice(), count) {
+                (Some(m), [path], Some(count)) => self.add_entry(&m, &resolve(base, path), count),
+                (Some(m), [], None) if messages.is_empty() => {
+                    self.disable_rules.push(m.rule.to_string())
+                }
+                _ => {
+                    let path = (!paths.is_empty()).then(|| {
+                        paths
+                            .iter()
+                            .map(|p| resolve(base, p))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    });
+                    self.unmapped.push(Unmapped {
+                        path,
+                        description: description.trim().to_string(),
+                        count: count.unwrap_or(1),
+                    });
+                }
+            }
+        }
+    }
+
+    fn add_psalm(&mut self, xml: &str, base: &Path) {
+        let mut file: Option<String> = None;
+        // (issue, occurrences attribute, <code> children seen)
+        let mut issue: Option<(String, Option<usize>, usize)> = None;
+        let mut in_ignore_files = false;
+        let mut in_issue_handlers = false;
+        // Issue handler currently open, and whether it is scoped to paths.
+        let mut handler: Option<(String, bool)> = None;
+
+        for tag in xml_tags(xml) {
+            match tag {
+                XmlTag::Open {
+                    name,
+                    attrs,
+                    closed,
+                } => match name.as_str() {
+                    "file" if file.is_none() && !in_ignore_files && handler.is_none() => {
+                        if let Some(src) = attrs.get("src") {
+                            file = Some(resolve(base, src));
+                        }
+                    }
+                    "ignoreFiles" => in_ignore_files = !closed,
+                    "issueHandlers" => in_issue_handlers = !closed,
+                    "directory" | "file" if in_ignore_files => {
+                        if let Some(name) = attrs.get("name") {
+                            self.exclude_paths.push(resolve(base, name));
+                        }
+                    }
+                    "code" if issue.is_some() => {
+                        if let Some((_, _, codes)) = issue.as_mut() {
+                            *codes += 1;
+                        }
+                    }
+                    _ if file.is_some() && issue.is_none() => {
+                        let occurrences = attrs.get("occurrences").and_then(|o| o.parse().ok());
+                        if closed {
+                            self.add_psalm_issue(&name, file.clone(), occurrences.unwrap_or(1));
+                        } else {
+                            issue = Some((name, occurrences, 0));
+                        }
+                    }
+                    "errorLevel"
+                        if handler.is_some()
+                            && attrs.get("type").map(String::as_str) == Some("suppress") =>
+                    {
+                        if let Some((_, scoped)) = handler.as_mut() {
+                            *scoped = true;
+                        }
+                    }
+                    _ if in_issue_handlers && handler.is_none() => {
+                        let suppressed =
+                            attrs.get("errorLevel").map(String::as_str) == Some("suppress");
+                        if suppressed {
+                            self.add_psalm_suppression(&name);
+                        }
+                        if !closed {
+                            handler = Some((name, false));
+                        }
+                    }
+                    _ => {}
+                },
+                XmlTag::Close { name } => match name.as_str() {
+                    "file" if issue.is_none() && !in_ignore_files => file = None,
+                    "ignoreFiles" => in_ignore_files = false,
+                    "issueHandlers" => in_issue_handlers = false,
+                    _ => {
+                        if issue.as_ref().is_some_and(|(n, _, _)| *n == name) {
+                            let (name, occurrences, codes) = issue.take().unwrap();
+                            let count = occurrences.unwrap_or(codes.max(1));
+                            self.add_psalm_issue(&name, file.clone(), count);
+                        } else if handler.as_ref().is_some_and(|(n, _)| *n == name) {
+                            let (name, scoped) = handler.take().unwrap();
+                            if scoped {
+                                self.unmapped.push(Unmapped {
+                                    path: None,
+                                    description: format!("{name} suppressed for some paths only"),
+                                    count: 1,
+                                });
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    fn add_psalm_issue(&mut self, issue: &str, file: Option<String>, count: usize) {
+        match (
+            mappings().into_iter().find(|m| m.psalm_issue == issue),
+            file,
+        ) {
+            (Some(m), Some(file)) => self.add_entry(&m, &file, count),
+            (_, path) => self.unmapped.push(Unmapped {
+                path,
+                description: issue.to_string(),
+                count,
+            }),
+        }
+    }
+
+    fn add_psalm_suppression(&mut self, issue: &str) {
+        match mappings().into_iter().find(|m| m.psalm_issue == issue) {
+            Some(m) => self.disable_rules.push(m.rule.to_string()),
+            None => self.unmapped.push(Unmapped {
+                path: None,
+                description: format!("{issue} suppressed"),
+                count: 1,
+            }),
+        }
+    }
+
+    fn add_entry(&mut self, mapping: &Mapping, path: &str, count: usize) {
+        let (dir, file) = split_dir_file(path);
+        self.baseline.violations.push(BaselineEntry {
+            path: dir,
+            file,
+            rule: mapping.rule.to_string(),
+            id: mapping.id.to_string(),
+            message: mapping.message.to_string(),
+            symbol: None,
+            fingerprint: None,
+            count,
+        });
+    }
+
+    /// Merge duplicate entries and sort everything so the output is stable.
+    fn finish(&mut self) {
+        let mut merged: HashMap<(String, String, String, String), BaselineEntry> = HashMap::new();
+        for entry in self.baseline.violations.drain(..) {
+            let key = (
+                entry.path.clone(),
+                entry.file.clone(),
+                entry.rule.clone(),
+                entry.id.clone(),
+            );
+            merged
+                .entry(key)
+                .and_modify(|e| e.count += entry.count)
+                .or_insert(entry);
+        }
+        let mut violations: Vec<BaselineEntry> = merged.into_values().collect();
+        violations.sort_by(|a, b| {
+            (&a.path, &a.file, &a.rule, &a.id).cmp(&(&b.path, &b.file, &b.rule, &b.id))
+        });
+        self.baseline.violations = violations;
+
+        self.exclude_paths.sort();
+        self.exclude_paths.dedup();
+        self.disable_rules.sort();
+        self.disable_rules.dedup();
+    }
+}
+
+/// A NEON value that may be a single string or a list of strings.
+fn list_of(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Resolve a path from a baseline or config file (relative to that file) to
+/// the normalized form baseline keys use. Globs and `%currentWorkingDirectory%`
+/// style placeholders are kept verbatim.
+fn resolve(base: &Path, path: &str) -> String {
+    let path = path
+        .strip_prefix("%currentWorkingDirectory%/")
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| base.join(path).to_string_lossy().to_string());
+
+    let mut parts: Vec<Component> = vec![];
+    for component in Path::new(&path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(parts.last(), Some(Component::Normal(_))) => {
+                parts.pop();
+            }
+            _ => parts.push(component),
+        }
+    }
+    normalize_relative(&parts.iter().collect::<PathBuf>())
+}
+
+/// Parse the subset of NEON that PHPStan configuration and baselines use:
+/// indented maps and `-` lists, quoted and plain scalars, and inline `[a, b]`
+/// lists. Entities and multi-line strings are not supported.
+pub fn parse_neon(text: &str) -> Result<Value, String> {
+    let lines: Vec<(usize, &str)> = text
+        .lines()
+        .filter_map(|line| {
+            let content = line.trim_start();
+            if content.is_empty() || content.starts_with('#') {
+                return None;
+            }
+            let indent = line.len() - content.len();
+            Some((indent, content.trim_end()))
+        })
+        .collect();
+
+    let mut i = 0;
+    let indent = lines.first().map(|(indent, _)| *indent).unwrap_or(0);
+    let value = parse_neon_block(&lines, &mut i, indent)?;
+    if i < lines.len() {
+        return Err(format!("unexpected indentation at \"{}\"", lines[i].1));
+    }
+    Ok(value)
+}
+
+fn parse_neon_block(
+    lines: &[(usize, &str)],
+    i: &mut usize,
+    indent: usize,
+) -> Result<Value, String> {
+    let is_list = lines
+        .get(*i)
+        .is_some_and(|(_, content)| *content == "-" || content.starts_with("- "));
+
+    if is_list {
+        let mut items = vec![];
+        while let Some(&(line_indent, content)) = lines.get(*i) {
+            if line_indent != indent || !(content == "-" || content.starts_with("- ")) {
+                break;
+            }
+            *i += 1;
+            let rest = content[1..].trim_start();
+            let item = if rest.is_empty() {
+                parse_neon_child(lines, i, indent)?
+            } else if let Some((key, value)) = split_neon_pair(rest) {
+                // `- key: value` opens a map whose other keys follow indented.
+                let mut map = Map::new();
+                let value = if value.is_empty() {
+                    parse_neon_child(lines, i, indent)?
+                } else {
+                    parse_neon_scalar(value)
+                };
+                map.insert(key, value);
+                if let Value::Object(rest) = parse_neon_child(lines, i, indent)? {
+                    map.extend(rest);
+                }
+                Value::Object(map)
+            } else {
+                parse_neon_scalar(rest)
+            };
+            items.push(item);
+        }
+        return Ok(Value::Array(items));
+    }
+
+    let mut map = Map::new();
+    while let Some(&(line_indent, content)) = lines.get(*i) {
+        if line_indent != indent {
+            break;
+        }
+        let (key, value) = split_neon_pair(content)
+            .ok_or_else(|| format!("expected \"key: value\" at \"{content}\""))?;
+        *i += 1;
+        let value = if value.is_empty() {
+            parse_neon_child(lines, i, indent)?
+        } else {
+            parse_neon_scalar(value)
+        };
+        map.insert(key, value);
+    }
+    Ok(Value::Object(map))
+}
+
+/// The block nested under the line just consumed, if the next line is indented
+/// deeper than `parent`.
+fn parse_neon_child(
+    lines: &[(usize, &str)],
+    i: &mut usize,
+    parent: usize,
+) -> Result<Value, String> {
+    match lines.get(*i) {
+        Some(&(indent, _)) if indent > parent => parse_neon_block(lines, i, indent),
+        _ => Ok(Value::Null),
+    }
+}
+
+fn split_neon_pair(content: &str) -> Option<(String, &str)> {
+    if content.starts_with(['"', '\'', '[']) {
+        return None;
+    }
+    let colon = content
+        .char_indices()
+        .find(|&(i, c)| {
+            c == ':'
+                && content[i + 1..]
+                    .chars()
+                    .next()
+                    .is_none_or(|n| n == ' ' || n == '\t')
+        })
+        .map(|(i, _)| i)?;
+    Some((
+        content[..colon].trim().to_string(),
+        content[colon + 1..].trim(),
+    ))
+}
+
+fn parse_neon_scalar(value: &str) -> Value {
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut out = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+        return Value::String(out);
+    }
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return Value::String(inner.replace("''", "'"));
+    }
+    if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return Value::Array(
+            inner
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(parse_neon_scalar)
+                .collect(),
+        );
+    }
+    match value {
+        "true" | "yes" | "on" => Value::Bool(true),
+        "false" | "no" | "off" => Value::Bool(false),
+        "null" => Value::Null,
+        _ => value
+            .parse::<u64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(value.to_string())),
+    }
+}
+
+enum XmlTag {
+    Open {
+        name: String,
+        attrs: HashMap<String, String>,
+        /// Self-closing (`<tag/>`).
+        closed: bool,
+    },
+    Close {
+        name: String,
+    },
+}
+
+/// The element tags of an XML document, skipping text, CDATA, comments and
+/// declarations. Enough for Psalm's baseline and configuration files.
+fn xml_tags(xml: &str) -> Vec<XmlTag> {
+    let mut tags = vec![];
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let (skip_to, terminator) = if rest.starts_with("<!--") {
+            (Some(4), "-->")
+        } else if rest.starts_with("<![CDATA[") {
+            (Some(9), "]]>")
+        } else {
+            (None, ">")
+        };
+        let from = skip_to.unwrap_or(1);
+        let Some(end) = rest[from..].find(terminator) else {
+            break;
+        };
+        let inner = &rest[1..from + end];
+        rest = &rest[from + end + terminator.len()..];
+
+        if skip_to.is_some() || inner.starts_with('?') || inner.starts_with('!') {
+            continue;
+        }
+        if let Some(name) = inner.strip_prefix('/') {
+            tags.push(XmlTag::Close {
+                name: name.trim().to_string(),
+            });
+            continue;
+        }
+
+        let closed = inner.ends_with('/');
+        let inner = inner.trim_end_matches('/');
+        let name_end = inner.find(char::is_whitespace).unwrap_or(inner.len());
+        let name = inner[..name_end].to_string();
+
+        let mut attrs = HashMap::new();
+        let mut attr_rest = &inner[name_end..];
+        while let Some(eq) = attr_rest.find('=') {
+            let key = attr_rest[..eq].trim().to_string();
+            let value_part = attr_rest[eq + 1..].trim_start();
+            let Some(quote) = value_part
+                .chars()
+                .next()
+                .filter(|q| *q == '"' || *q == '\'')
+            else {
+                break;
+            };
+            let Some(value_end) = value_part[1..].find(quote) else {
+                break;
+            };
+            let value = value_part[1..1 + value_end]
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&");
+            attrs.insert(key, value);
+            attr_rest = &value_part[value_end + 2..];
+        }
+
+        tags.push(XmlTag::Open {
+            name,
+            attrs,
+            closed,
+        });
+    }
+
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_text(name: &str, text: &str) -> Import {
+        let dir = std::env::temp_dir().join(format!("phanalist_import_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, text).unwrap();
+        let import = Import::from_files(&[path]);
+        std::fs::remove_dir_all(&dir).ok();
+        import.unwrap()
+    }
+
+    #[test]
+    fn parse_neon_reads_phpstan_baseline_shape() {
+        let neon = "parameters:\n\tignoreErrors:\n\t\t-\n\t\t\tmessage: \"#^Method Foo\\\\:\\\\:bar\\\\(\\\\) is unused\\\\.$#\"\n\t\t\tcount: 2\n\t\t\tpath: src/Foo.php\n\t\t- message: '#^It''s fine$#'\n\t\t  path: src/Bar.php\n\texcludePaths: [vendor, var/cache]\n";
+        let value = parse_neon(neon).unwrap();
+
+        let errors = value["parameters"]["ignoreErrors"].as_array().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0]["message"],
+            "#^Method Foo\\:\\:bar\\(\\) is unused\\.$#"
+        );
+        assert_eq!(errors[0]["count"], 2);
+        assert_eq!(errors[1]["message"], "#^It's fine$#");
+        assert_eq!(errors[1]["path"], "src/Bar.php");
+        assert_eq!(
+            value["parameters"]["excludePaths"],
+            serde_json::json!(["vendor", "var/cache"])
+        );
+    }
+
+    #[test]
+    fn phpstan_baseline_maps_overlapping_identifiers() {
+        let neon = "parameters:\n    ignoreErrors:\n        -\n            message: '#^Method App\\\\Foo\\:\\:bar\\(\\) has no return type specified\\.$#'\n            identifier: missingType.return\n            count: 3\n            path: src/Foo.php\n        -\n            message: '#^Access to an undefined property#'\n            identifier: property.notFound\n            count: 1\n            path: src/Foo.php\n";
+        let import = import_text("phpstan-baseline.neon", neon);
+
+        assert_eq!(import.baseline.version, 1);
+        assert_eq!(import.baseline.violations.len(), 1);
+        let entry = &import.baseline.violations[0];
+        assert_eq!(entry.rule, "E0008");
+        assert_eq!(entry.id, "E0008:missing-return-type");
+        assert_eq!(entry.count, 3);
+        assert_eq!(entry.file, "Foo.php");
+        assert!(
+            entry.path.ends_with("src"),
+            "unexpected path {}",
+            entry.path
+        );
+
+        assert_eq!(import.unmapped.len(), 1);
+        assert!(import.unmapped[0]
+            .description
+            .starts_with("property.notFound"));
+    }
+
+    #[test]
+    fn phpstan_message_fallback_only_maps_methods() {
+        let neon = "parameters:\n    ignoreErrors:\n        -\n            message: '#^Method App\\\\Foo\\:\\:bar\\(\\) has no return type specified\\.$#'\n            count: 2\n            path: src/Foo.php\n        -\n            message: '#^Function helper\\(\\) has no return type specified\\.$#'\n            count: 1\n            path: src/functions.php\n";
+        let import = import_text("phpstan-baseline.neon", neon);
+
+        assert_eq!(import.baseline.violations.len(), 1);
+        assert_eq!(import.baseline.violations[0].file, "Foo.php");
+        assert_eq!(import.unmapped.len(), 1);
+        assert!(import.unmapped[0]
+            .description
+            .starts_with("#^Function helper"));
+    }
+
+    #[test]
+    fn phpstan_identifier_without_path_disables_the_rule() {
+        let neon = "parameters:\n    ignoreErrors:\n        - identifier: method.unused\n";
+        let import = import_text("phpstan.neon", neon);
+
+        assert_eq!(import.disable_rules, vec!["E0013".to_string()]);
+        assert!(import.baseline.violations.is_empty());
+    }
+
+    #[test]
+    fn psalm_baseline_counts_code_children_and_occurrences() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<files psalm-version="5.0.0">
+  <file src="src/Foo.php">
+    <MissingReturnType>
+      <code><![CDATA[bar]]></code>
+      <code><![CDATA[baz]]></code>
+    </MissingReturnType>
+    <UnusedMethod occurrences="1">
+      <code>secret</code>
+    </UnusedMethod>
+    <PossiblyNullReference>
+      <code>foo</code>
+    </PossiblyNullReference>
+  </file>
+</files>
+"#;
+        let import = import_text("psalm-baseline.xml", xml);
+
+        let counts: Vec<(&str, usize)> = import
+            .baseline
+            .violations
+            .iter()
+            .map(|e| (e.rule.as_str(), e.count))
+            .collect();
+        assert_eq!(counts, vec![("E0008", 2), ("E0013", 1)]);
+        assert_eq!(import.unmapped.len(), 1);
+        assert_eq!(import.unmapped[0].description, "PossiblyNullReference");
+    }
+
+    #[test]
+    fn psalm_config_maps_ignored_files_and_suppressed_issues() {
+        let xml = r#"<?xml version="1.0"?>
+<psalm>
+  <projectFiles>
+    <directory name="src" />
+    <ignoreFiles>
+      <directory name="vendor" />
+    </ignoreFiles>
+  </projectFiles>
+  <issueHandlers>
+    <MissingReturnType errorLevel="suppress" />
+    <UnusedMethod>
+      <errorLevel type="suppress">
+        <directory name="src/Legacy" />
+      </errorLevel>
+    </UnusedMethod>
+  </issueHandlers>
+</psalm>
+"#;
+        let import = import_text("psalm.xml", xml);
+
+        assert_eq!(import.disable_rules, vec!["E0008".to_string()]);
+        assert_eq!(import.exclude_paths.len(), 1);
+        assert!(import.exclude_paths[0].ends_with("vendor"));
+        assert_eq!(import.unmapped.len(), 1);
+        assert!(import.unmapped[0].description.contains("UnusedMethod"));
+    }
+
+    #[test]
+    fn unsupported_extension_is_an_error() {
+        let path = PathBuf::from("baseline.txt");
+        assert!(Import::from_files(&[path]).is_err());
+    }
+}
@@ -2,13 +2,20 @@ use analyse::Analyse;
 use outputs::Format;
 pub mod analyse;
 pub mod baseline;
+pub mod codeowners;
+pub mod composer;
 pub mod config;
 pub mod debug_stats;
 pub mod engineer;
 pub mod file;
+pub mod git;
+pub mod history;
+pub mod hotspots;
+pub mod import;
 pub mod lsp;
 pub mod outputs;
 pub mod paths;
+pub mod project_index;
 pub mod results;
 pub mod rules;
 
@@ -18,7 +25,7 @@ pub fn scan(path: String) -> results::Results {
 
     let analyze: Analyse = Analyse::new(&config);
 
-    analyze.scan("./src".to_string(), &config, false, &output_format,0,false,None)
+    analyze.scan("./src".to_string(), &config, false, &output_format,0,false,None,None)
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 use std::error::Error;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use lsp_server::{Connection, Message, Notification, Response};
 use lsp_types::{
@@ -13,6 +14,7 @@ use serde_json::Value;
 use crate::analyse::Analyse;
 use crate::config::Config;
 use crate::file::File;
+use crate::project_index::ProjectIndex;
 
 /// Start the LSP stdio server and listen for requests/notifications from the IDE client.
 pub fn run_server(config: &Config) -> Result<(), Box<dyn Error>> {
@@ -89,21 +91,21 @@ pub fn run_server(config: &Config) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-/// Index files in the workspace on startup to populate rule internal models (e.g. class hierarchies/extends)
+/// Index files in the workspace on startup to build the project index (e.g. class hierarchies)
 fn index_workspace(analyse: &Analyse, root: &Path, config: &Config) {
     let (send, recv) = std::sync::mpsc::channel();
     let exclude_paths = config.exclude_paths.clone();
     crate::analyse::scan_folder(root.to_path_buf(), send, 0, None, exclude_paths);
 
     let arena = LocalArena::new();
+    let mut project = ProjectIndex::default();
     let mut count = 0;
     for (content, path) in recv {
         let file = File::new(&arena, path, content);
-        for rule in analyse.rules.values() {
-            rule.index_file(&file);
-        }
+        project.add_file(&file);
         count += 1;
     }
+    analyse.set_project(project);
     eprintln!("Indexed {} workspace files.", count);
 }
 
@@ -149,9 +151,11 @@ pub(crate) fn analyze_single_file(
     let arena = LocalArena::new();
     let mut file = File::new(&arena, path, content);
 
-    // Re-run indexing for this file to update the rule indices with the latest content
-    for rule in analyse.rules.values() {
-        rule.index_file(&file);
+    // Re-index this file so the project index reflects the latest content
+    if let Ok(mut project) = analyse.project.write() {
+        let project = Arc::make_mut(&mut project);
+        project.remove_file(&file.path);
+        project.add_file(&file);
     }
 
     // Run active static analysis rules
@@ -160,18 +164,24 @@ pub(crate) fn analyze_single_file(
     // Map Phanalist violations to LSP Diagnostics (0-indexed line and columns)
     let diagnostics: Vec<Diagnostic> = violations
         .into_iter()
+        .filter(|violation| !violation.inline_suppressed)
         .map(|violation| {
             let start_line = violation.start_line.saturating_sub(1) as u32;
             let start_col = violation.start_column as u32;
             let end_line = violation.end_line.saturating_sub(1) as u32;
             let end_col = violation.end_column as u32;
+            let severity = if violation.is_error() {
+                DiagnosticSeverity::ERROR
+            } else {
+                DiagnosticSeverity::WARNING
+            };
 
             Diagnostic {
                 range: Range::new(
                     Position::new(start_line, start_col),
                     Position::new(end_line, end_col),
                 ),
-                severity: Some(DiagnosticSeverity::WARNING),
+                severity: Some(severity),
                 code: Some(lsp_types::NumberOrString::String(violation.rule)),
                 code_description: None,
                 source: Some("phanalist".to_string()),
@@ -240,6 +250,21 @@ mod tests {
         assert!(d.message.contains("8"), "message should mention the max parameter count: {}", d.message);
     }
 
+    #[test]
+    fn parse_errors_are_error_diagnostics() {
+        let config = Config::default();
+        let analyse = Analyse::new(&config);
+        let content = "<?php\n\n$total = 0\nreturn $total;\n".to_string();
+
+        let diagnostics = analyze_single_file(&analyse, &make_uri("/broken.php"), content).unwrap();
+        let d = diagnostics.iter().find(|d| {
+            matches!(&d.code, Some(NumberOrString::String(code)) if code == "E0031")
+        }).expect("expected E0031 diagnostic");
+
+        assert_eq!(d.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(d.range.start.line, 3, "expected the error on the `return` line");
+    }
+
     #[test]
     fn diagnostics_use_zero_indexed_lines() {
         let config = Config::default();
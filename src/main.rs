@@ -1,6 +1,6 @@
 extern crate exitcode;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::str::FromStr;
 
@@ -10,17 +10,24 @@ use indicatif::ProgressBar;
 
 use crate::analyse::Analyse;
 use crate::baseline::Baseline;
-use crate::engineer::{BlameConfig, EngineerBlame};
+use crate::engineer::{BlameConfig, EngineerBlame, GroupBy};
 use crate::outputs::Format;
 
 mod analyse;
 mod baseline;
+mod codeowners;
+mod composer;
 mod config;
 mod debug_stats;
 mod engineer;
 mod file;
+mod git;
+mod history;
+mod hotspots;
+mod import;
 mod outputs;
 mod paths;
+mod project_index;
 mod results;
 mod lsp;
 mod rules;
@@ -32,13 +39,14 @@ mod rules;
 struct Args {
     #[arg(short, long, default_value = "./phanalist.yaml")]
     config: String,
-    #[arg(short, long, default_values_t = ["./src".to_string()])]
+    #[arg(short, long)]
+    /// Paths to analyse (default: the PSR-4 and classmap autoload roots of ./composer.json, else ./src)
     src: Vec<String>,
     #[arg(short, long)]
     /// The list of rules to use (by default it is used from config)
     rules: Option<Vec<String>>,
     #[arg(short, long, default_value = "text")]
-    /// Possible options: text, json, sarif, codeclimate
+    /// Possible options: text, json, sarif, codeclimate, csv, ndjson (html for --hotspots, markdown for --blame)
     output_format: String,
     #[arg(long)]
     /// Output only summary
@@ -63,9 +71,39 @@ struct Args {
     /// Discard the existing baseline and regenerate it from the current scan (requires --use-baseline)
     update_baseline: bool,
     #[arg(long)]
+    /// Lower the baseline to the violations still present and drop fixed entries, never adding new ones (requires --use-baseline)
+    prune_baseline: bool,
+    #[arg(long)]
+    /// Convert a PHPStan (.neon) or Psalm (.xml) baseline or config into the --use-baseline file and matching config (repeatable)
+    import_baseline: Vec<String>,
+    #[arg(long)]
+    /// Only validate PHP files changed between this git ref and the working tree, including untracked files (the whole project is still indexed)
+    changed_since: Option<String>,
+    #[arg(long)]
+    /// Only report violations on lines added or modified since this git ref (class-level violations count when their span overlaps a change)
+    only_changed_lines: Option<String>,
+    #[arg(long)]
+    /// Only validate staged PHP files, analysing their content from the git index (for pre-commit hooks)
+    staged: bool,
+    #[arg(long)]
+    /// Analyse the tree of this commit-ish (tag, branch, sha) straight from git, without checking it out
+    rev: Option<String>,
+    #[arg(long)]
+    /// Show how violation counts per rule changed over git history, sampling commits weekly, monthly or every N commits (window set by --since/--until)
+    history: Option<String>,
+    #[arg(long)]
+    /// Rank each file, class or method by the commits touching it in the --since/--until window times its complexity
+    hotspots: Option<String>,
+    #[arg(long, default_value = "cognitive")]
+    /// Complexity the hotspot churn is multiplied by: cognitive (E0016, default), cyclomatic (E0009) or lines (E0024)
+    hotspot_metric: String,
+    #[arg(long)]
     /// Attribute violations to engineers via git blame and show a quality report
     blame: bool,
     #[arg(long)]
+    /// Export the engineer report as an SVG or PNG chart to this path, picked by its extension (requires --blame)
+    export_chart: Option<String>,
+    #[arg(long)]
     /// Show violations introduced since this date (e.g. "30 days", "1 year", "2025-01-01")
     since: Option<String>,
     #[arg(long)]
@@ -87,6 +125,18 @@ struct Args {
     /// Sort the engineer report by: total (default), net, name, fixed, introduced
     sort: String,
     #[arg(long)]
+    /// With --blame --since, credit each introduced or fixed violation to the commit that changed its lines
+    commits: bool,
+    #[arg(long, default_value = "engineer")]
+    /// Group the engineer report by: engineer (default), team or owner (from CODEOWNERS), directory
+    group_by: String,
+    #[arg(long)]
+    /// Exit with an error once a scanned path has been analysed if any of its files failed to parse, listing the parse errors (E0031) instead of the report
+    fatal_parse_errors: bool,
+    #[arg(long)]
+    /// Index the composer vendor directory for its types only, without reporting violations in it
+    index_vendor: bool,
+    #[arg(long)]
     /// Start as a Language Server (LSP) for editor integrations
     lsp: bool,
 }
@@ -109,6 +159,14 @@ fn main() {
     if let Some(rules) = args.rules.clone() {
         config.enabled_rules = rules;
     }
+    if args.fatal_parse_errors {
+        // Parse errors can only be fatal when they are checked.
+        let code = rules::e31::CODE.to_string();
+        config.disable_rules.retain(|disabled| *disabled != code);
+        if !config.enabled_rules.is_empty() && !config.enabled_rules.contains(&code) {
+            config.enabled_rules.push(code);
+        }
+    }
 
     if args.lsp {
         match lsp::run_server(&config) {
@@ -120,9 +178,48 @@ fn main() {
         }
     }
 
-    let paths = args.src;
+    // Converting baselines scans nothing, so it does not need the source paths.
+    if !args.import_baseline.is_empty() {
+        import_baselines(&args.import_baseline, args.use_baseline.as_deref());
+    }
+
+    let composer = match composer::Composer::discover(Path::new(".")) {
+        Ok(composer) => composer,
+        Err(msg) => {
+            eprintln!("{msg}");
+            None
+        }
+    };
+
+    let mut paths = args.src;
+    if paths.is_empty() {
+        let roots = composer
+            .as_ref()
+            .map(composer::Composer::source_roots)
+            .unwrap_or_default();
+        if roots.is_empty() {
+            paths.push("./src".to_string());
+        } else {
+            if args.verbose >= 1 {
+                eprintln!("Autoload roots from composer.json:");
+                for root in &roots {
+                    eprintln!("  {}", root.display());
+                }
+            }
+            paths.extend(roots.iter().map(|root| root.display().to_string()));
+            // A root may be the project itself, whose dependencies are not ours
+            // to report. With --index-vendor they are skipped as types-only.
+            let vendor_dir = composer.as_ref().map(composer::Composer::vendor_dir);
+            if let Some(vendor_dir) = vendor_dir.filter(|dir| dir.is_dir() && !args.index_vendor) {
+                config
+                    .exclude_paths
+                    .push(paths::normalize_relative(&vendor_dir));
+            }
+        }
+    }
     for path in paths.iter() {
-        if !Path::new(&path).exists() {
+        // With --rev the sources are read from git and may not exist on disk.
+        if args.rev.is_none() && !Path::new(&path).exists() {
             println!("Path {} does not exist", path);
             process::exit(exitcode::IOERR);
         }
@@ -130,12 +227,198 @@ fn main() {
 
     let mut analyze = Analyse::new(&config);
 
+    if args.index_vendor {
+        let vendor_dir = composer
+            .as_ref()
+            .map(composer::Composer::vendor_dir)
+            .unwrap_or_else(|| PathBuf::from("./vendor"));
+        if !vendor_dir.is_dir() {
+            eprintln!("--index-vendor: {} does not exist", vendor_dir.display());
+            process::exit(exitcode::IOERR);
+        }
+        analyze.types_only.push(vendor_dir);
+        analyze.index_types_only(&config, args.verbose);
+    }
+
     if args.update_baseline && args.use_baseline.is_none() {
         eprintln!("--update-baseline requires --use-baseline <path>");
         process::exit(exitcode::USAGE);
     }
 
-    // In filter mode (use-baseline without update) load the baseline up front.
+    if args.prune_baseline && args.use_baseline.is_none() {
+        eprintln!("--prune-baseline requires --use-baseline <path>");
+        process::exit(exitcode::USAGE);
+    }
+
+    if args.prune_baseline && args.update_baseline {
+        eprintln!("--prune-baseline and --update-baseline cannot be combined");
+        process::exit(exitcode::USAGE);
+    }
+
+    if (args.changed_since.is_some() || args.only_changed_lines.is_some()) && args.update_baseline {
+        eprintln!("--changed-since/--only-changed-lines and --update-baseline cannot be combined");
+        process::exit(exitcode::USAGE);
+    }
+
+    if args.staged && (args.changed_since.is_some() || args.only_changed_lines.is_some()) {
+        eprintln!("--staged cannot be combined with --changed-since/--only-changed-lines");
+        process::exit(exitcode::USAGE);
+    }
+
+    if args.rev.is_some()
+        && (args.staged
+            || args.changed_since.is_some()
+            || args.only_changed_lines.is_some()
+            || args.blame)
+    {
+        eprintln!("--rev cannot be combined with --staged, --changed-since, --only-changed-lines or --blame");
+        process::exit(exitcode::USAGE);
+    }
+
+    if args.export_chart.is_some() && !args.blame {
+        eprintln!("--export-chart requires --blame");
+        process::exit(exitcode::USAGE);
+    }
+
+    let group_by: GroupBy = match args.group_by.parse() {
+        Ok(group_by) => group_by,
+        Err(msg) => {
+            eprintln!("{msg}");
+            process::exit(exitcode::USAGE);
+        }
+    };
+    if group_by != GroupBy::Engineer && !args.blame {
+        eprintln!("--group-by requires --blame");
+        process::exit(exitcode::USAGE);
+    }
+
+    if args.commits && !(args.blame && args.since.is_some()) {
+        eprintln!("--commits requires --blame and --since");
+        process::exit(exitcode::USAGE);
+    }
+
+    if let Some(rev) = &args.rev {
+        match git::Revision::resolve(Path::new(&paths[0]), rev) {
+            Ok(revision) => analyze.revision = Some(revision),
+            Err(msg) => {
+                eprintln!("{msg}");
+                process::exit(exitcode::USAGE);
+            }
+        }
+    }
+
+    if let Some(interval) = &args.history {
+        let conflicting = args.blame
+            || args.rev.is_some()
+            || args.staged
+            || args.changed_since.is_some()
+            || args.only_changed_lines.is_some()
+            || args.use_baseline.is_some();
+        if conflicting {
+            eprintln!("--history cannot be combined with --blame, --rev, --staged, --changed-since, --only-changed-lines or --use-baseline");
+            process::exit(exitcode::USAGE);
+        }
+        run_history(
+            &paths,
+            &config,
+            interval,
+            (&args.since, &args.until),
+            &format,
+            quiet,
+        );
+    }
+
+    if let Some(level) = &args.hotspots {
+        let conflicting = args.blame
+            || args.history.is_some()
+            || args.rev.is_some()
+            || args.staged
+            || args.changed_since.is_some()
+            || args.only_changed_lines.is_some()
+            || args.use_baseline.is_some();
+        if conflicting {
+            eprintln!("--hotspots cannot be combined with --blame, --history, --rev, --staged, --changed-since, --only-changed-lines or --use-baseline");
+            process::exit(exitcode::USAGE);
+        }
+        run_hotspots(
+            &paths,
+            &config,
+            (level, &args.hotspot_metric),
+            (&args.since, &args.until),
+            &format,
+            quiet,
+        );
+    }
+
+    if format == Format::html {
+        eprintln!("The html output format is only available for --hotspots");
+        process::exit(exitcode::USAGE);
+    }
+    if format == Format::markdown && !args.blame {
+        eprintln!("The markdown output format is only available for --blame");
+        process::exit(exitcode::USAGE);
+    }
+    if format == Format::ndjson && args.blame {
+        eprintln!("The ndjson output format is not available for --blame");
+        process::exit(exitcode::USAGE);
+    }
+
+    if args.staged && args.update_baseline {
+        eprintln!("--staged and --update-baseline cannot be combined");
+        process::exit(exitcode::USAGE);
+    }
+
+    if args.staged {
+        match git::ChangedFiles::staged(Path::new(&paths[0])) {
+            Ok(staged) => {
+                if staged.is_empty() && !quiet && format == Format::text {
+                    println!("No staged PHP files.");
+                } else if args.verbose >= 1 {
+                    eprintln!("{} staged PHP files", staged.len());
+                }
+                analyze.changed_files = Some(staged);
+            }
+            Err(msg) => {
+                eprintln!("{msg}");
+                process::exit(exitcode::USAGE);
+            }
+        }
+    }
+
+    // Files without changed lines cannot report anything, so only validate the
+    // changed files unless --changed-since picks another ref.
+    if let Some(reference) = args
+        .changed_since
+        .as_ref()
+        .or(args.only_changed_lines.as_ref())
+    {
+        match git::ChangedFiles::since(Path::new(&paths[0]), reference) {
+            Ok(changed) => {
+                if changed.is_empty() && !quiet && format == Format::text {
+                    println!("No PHP files changed since {reference}.");
+                } else if args.verbose >= 1 {
+                    eprintln!("{} PHP files changed since {reference}", changed.len());
+                }
+                analyze.changed_files = Some(changed);
+            }
+            Err(msg) => {
+                eprintln!("{msg}");
+                process::exit(exitcode::USAGE);
+            }
+        }
+    }
+
+    let changed_lines = args.only_changed_lines.as_ref().map(|reference| {
+        match git::ChangedLines::since(Path::new(&paths[0]), reference) {
+            Ok(lines) => lines,
+            Err(msg) => {
+                eprintln!("{msg}");
+                process::exit(exitcode::USAGE);
+            }
+        }
+    });
+
+    // In filter and prune mode (use-baseline without update) load the baseline up front.
     let baseline = match (&args.use_baseline, args.update_baseline) {
         (Some(path), false) => match Baseline::load(Path::new(path)) {
             Ok(b) => Some(b),
@@ -166,8 +449,35 @@ fn main() {
         None
     };
 
+    // NDJSON is written while scanning, one file at a time, so violations
+    // never pile up in memory. Modes that need all of them at once collect.
+    let stream = format == Format::ndjson
+        && !(args.update_baseline || args.prune_baseline || args.fatal_parse_errors);
+
     // Silence unused warning when --blame is not used
     for path in paths.iter() {
+        // Baseline entries are per file, so a file's fixed ones are known as
+        // soon as it is analysed.
+        let mut streamed_fixed = Vec::new();
+        let mut write_file = |path: String, violations: Vec<results::Violation>| {
+            let mut file = results::Results::default();
+            file.files.insert(path.clone(), violations);
+            file.remove_inline_suppressed();
+            if let Some(ref baseline) = baseline {
+                streamed_fixed.extend(baseline.fixed_entries(&file));
+                baseline.filter(&mut file);
+            }
+            if let Some(ref changed_lines) = changed_lines {
+                changed_lines.filter(&mut file);
+            }
+            let violations = file.files.remove(&path).unwrap_or_default();
+            if !quiet && !args.summary_only {
+                let _ =
+                    outputs::ndjson::NdJson::write_file(&mut std::io::stdout(), &path, &violations);
+            }
+            violations
+        };
+
         let mut results = analyze.scan(
             path.clone(),
             &config,
@@ -176,17 +486,44 @@ fn main() {
             args.verbose,
             collect_rule_metrics,
             blame_bar.clone(),
+            stream.then_some(
+                &mut write_file
+                    as &mut dyn FnMut(String, Vec<results::Violation>) -> Vec<results::Violation>,
+            ),
         );
 
-        // Update mode: collect every violation for the new baseline and skip
-        // per-path output entirely.
-        if args.update_baseline {
+        if args.fatal_parse_errors {
+            exit_on_parse_errors(&results);
+        }
+
+        // Update and prune mode: collect every violation for the new baseline
+        // and skip per-path output entirely.
+        if args.update_baseline || args.prune_baseline {
             aggregate.files.extend(results.files);
             continue;
         }
 
-        if let Some(ref baseline) = baseline {
-            baseline.filter(&mut results);
+        // SARIF reports violations silenced inline as suppressed; the other
+        // formats leave them out.
+        if format != Format::sarif {
+            results.remove_inline_suppressed();
+        }
+
+        if stream {
+            results.baseline_fixed = streamed_fixed;
+        } else if let Some(ref baseline) = baseline {
+            results.baseline_fixed = baseline.fixed_entries(&results);
+            // SARIF consumers track baselined results themselves, so keep them
+            // marked as unchanged/absent instead of dropping them.
+            if format == Format::sarif {
+                baseline.mark(&mut results);
+            } else {
+                baseline.filter(&mut results);
+            }
+        }
+
+        if let Some(ref changed_lines) = changed_lines {
+            changed_lines.filter(&mut results);
         }
 
         if !quiet && !args.blame {
@@ -246,6 +583,31 @@ fn main() {
         }
     }
 
+    if args.prune_baseline {
+        let path = args.use_baseline.expect("validated above");
+        let baseline = baseline.expect("loaded above");
+        let pruned = baseline.prune(&aggregate, &paths);
+        let removed: usize = baseline.violations.iter().map(|e| e.count).sum::<usize>()
+            - pruned.violations.iter().map(|e| e.count).sum::<usize>();
+        match pruned.save(&std::path::PathBuf::from(&path)) {
+            Ok(()) => {
+                if !quiet && format == Format::text {
+                    println!(
+                        "Baseline {} pruned: {} fixed violations removed ({} entries left).",
+                        path,
+                        removed,
+                        pruned.violations.len()
+                    );
+                }
+                process::exit(exitcode::OK);
+            }
+            Err(e) => {
+                eprintln!("Failed to write baseline {path}: {e}");
+                process::exit(exitcode::CANTCREAT);
+            }
+        }
+    }
+
     // Git repo discovered after scanning to avoid FFI/library conflicts
     let blame_git_repo = if args.blame {
         let src_path = Path::new(&paths[0]);
@@ -292,6 +654,9 @@ fn main() {
             filter_authors: args.filter_author.clone(),
             filter_rules: args.filter_rule.clone(),
             sort_by: args.sort.clone(),
+            identities: config.identities.clone(),
+            group_by,
+            track_commits: args.commits,
         };
 
         let engineer = match EngineerBlame::new(&repo_root, &blame_config) {
@@ -317,11 +682,46 @@ fn main() {
             b.finish();
         }
 
+        if let Some(path) = &args.export_chart {
+            let path = Path::new(path);
+            if let Err(msg) = outputs::chart_export::export_chart(
+                &report,
+                &args.since,
+                &args.sort,
+                group_by,
+                path,
+            ) {
+                eprintln!("{msg}");
+                process::exit(exitcode::CANTCREAT);
+            }
+            if !quiet && format == Format::text {
+                println!("Chart written to {}", path.display());
+            }
+        }
+
         if !quiet {
-            if format == Format::text {
-                outputs::chart::print_engineer_report(&report, &args.since, &args.sort);
-            } else if format == Format::json {
-                println!("{}", serde_json::to_string_pretty(&aggregate).unwrap());
+            match format {
+                Format::text => outputs::chart::print_engineer_report(
+                    &report,
+                    &args.since,
+                    &args.sort,
+                    group_by,
+                ),
+                Format::json => println!("{}", serde_json::to_string_pretty(&aggregate).unwrap()),
+                Format::csv => print!(
+                    "{}",
+                    outputs::chart::engineer_report_csv(&report, &args.sort, group_by)
+                ),
+                Format::markdown => print!(
+                    "{}",
+                    outputs::chart::engineer_report_markdown(
+                        &report,
+                        &args.since,
+                        &args.sort,
+                        group_by
+                    )
+                ),
+                _ => {}
             }
         }
 
@@ -333,3 +733,231 @@ fn main() {
         process::exit(exitcode::OK);
     }
 }
+
+/// With `--fatal-parse-errors`, list the parse errors found in `results` and
+/// exit if there are any.
+fn exit_on_parse_errors(results: &results::Results) {
+    let mut errors: Vec<(&String, &results::Violation)> = results
+        .files
+        .iter()
+        .flat_map(|(path, violations)| {
+            violations
+                .iter()
+                .filter(|violation| violation.rule == rules::e31::CODE)
+                .map(move |violation| (path, violation))
+        })
+        .collect();
+    if errors.is_empty() {
+        return;
+    }
+
+    errors.sort_by_key(|(path, violation)| {
+        (path.as_str(), violation.start_line, violation.start_column)
+    });
+    for (path, violation) in errors {
+        eprintln!(
+            "{path}:{}:{}: {}",
+            violation.start_line,
+            violation.start_column,
+            violation.message.render()
+        );
+    }
+    process::exit(exitcode::DATAERR);
+}
+
+/// Sample the git history, analyse every snapshot and print the time series.
+/// Never returns.
+fn run_history(
+    paths: &[String],
+    config: &config::Config,
+    interval: &str,
+    (since, until): (&Option<String>, &Option<String>),
+    format: &Format,
+    quiet: bool,
+) {
+    let interval: history::Interval = match interval.parse() {
+        Ok(i) => i,
+        Err(msg) => {
+            eprintln!("{msg}");
+            process::exit(exitcode::USAGE);
+        }
+    };
+    let (since_ts, until_ts) = parse_window(since, until);
+
+    let bar = if format == &Format::text && !quiet {
+        Some(ProgressBar::new(0))
+    } else {
+        None
+    };
+    let history = match history::collect(paths, config, interval, since_ts, until_ts, bar.as_ref())
+    {
+        Ok(h) => h,
+        Err(msg) => {
+            eprintln!("{msg}");
+            process::exit(exitcode::DATAERR);
+        }
+    };
+    if let Some(bar) = bar {
+        bar.finish_and_clear();
+    }
+
+    if !quiet {
+        match format {
+            Format::json => println!("{}", serde_json::to_string_pretty(&history).unwrap()),
+            Format::csv => print!("{}", outputs::history::history_csv(&history)),
+            Format::text => outputs::history::print_history(&history),
+            _ => {
+                eprintln!("--history supports the text, json and csv output formats");
+                process::exit(exitcode::USAGE);
+            }
+        }
+    }
+    process::exit(exitcode::OK);
+}
+
+/// `--since` and `--until` as timestamps, exiting on an invalid date.
+fn parse_window(since: &Option<String>, until: &Option<String>) -> (Option<i64>, Option<i64>) {
+    let parse_date = |flag: &str, value: &Option<String>| {
+        value
+            .as_ref()
+            .map(|v| match engineer::parse_relative_date(v) {
+                Some(dt) => dt.timestamp(),
+                None => {
+                    eprintln!("Invalid {flag} date: {v}");
+                    process::exit(exitcode::USAGE);
+                }
+            })
+    };
+    (parse_date("--since", since), parse_date("--until", until))
+}
+
+/// Compute and print the `--hotspots` report. Never returns.
+fn run_hotspots(
+    paths: &[String],
+    config: &config::Config,
+    (level, metric): (&str, &str),
+    (since, until): (&Option<String>, &Option<String>),
+    format: &Format,
+    quiet: bool,
+) {
+    let level: hotspots::Level = level.parse().unwrap_or_else(|msg: String| {
+        eprintln!("{msg}");
+        process::exit(exitcode::USAGE);
+    });
+    let metric: hotspots::Metric = metric.parse().unwrap_or_else(|msg: String| {
+        eprintln!("{msg}");
+        process::exit(exitcode::USAGE);
+    });
+    if !matches!(format, Format::text | Format::json | Format::html) {
+        eprintln!("--hotspots supports the text, json and html output formats");
+        process::exit(exitcode::USAGE);
+    }
+    let (since_ts, until_ts) = parse_window(since, until);
+
+    let bar = if format == &Format::text && !quiet {
+        Some(ProgressBar::new(0))
+    } else {
+        None
+    };
+    let report = match hotspots::collect(
+        paths,
+        config,
+        level,
+        metric,
+        since_ts,
+        until_ts,
+        bar.as_ref(),
+    ) {
+        Ok(r) => r,
+        Err(msg) => {
+            eprintln!("{msg}");
+            process::exit(exitcode::DATAERR);
+        }
+    };
+    if let Some(bar) = bar {
+        bar.finish_and_clear();
+    }
+
+    if !quiet {
+        match format {
+            Format::json => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+            Format::html => print!("{}", outputs::hotspots::hotspots_html(&report)),
+            _ => outputs::hotspots::print_hotspots(&report),
+        }
+    }
+    process::exit(exitcode::OK);
+}
+
+/// Convert PHPStan/Psalm files, write the baseline part to `baseline_path` and
+/// print the config part and whatever could not be mapped. Never returns.
+fn import_baselines(files: &[String], baseline_path: Option<&str>) {
+    let paths: Vec<std::path::PathBuf> = files.iter().map(std::path::PathBuf::from).collect();
+    let imported = match import::Import::from_files(&paths) {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(exitcode::DATAERR);
+        }
+    };
+
+    let entries = imported.baseline.violations.len();
+    if entries > 0 {
+        let Some(path) = baseline_path else {
+            eprintln!("--import-baseline found {entries} baseline entries and requires --use-baseline <path> to write them");
+            process::exit(exitcode::USAGE);
+        };
+        if Path::new(path).exists() {
+            eprintln!(
+                "Baseline {path} already exists; remove it or pass another --use-baseline path"
+            );
+            process::exit(exitcode::CANTCREAT);
+        }
+        if let Err(e) = imported.baseline.save(&std::path::PathBuf::from(path)) {
+            eprintln!("Failed to write baseline {path}: {e}");
+            process::exit(exitcode::CANTCREAT);
+        }
+        println!("Baseline written to {path} ({entries} entries).");
+    }
+
+    if !imported.exclude_paths.is_empty() || !imported.disable_rules.is_empty() {
+        println!();
+        println!("{}", "Add to your phanalist.yaml:".bold());
+        if !imported.disable_rules.is_empty() {
+            println!("disable_rules:");
+            for rule in &imported.disable_rules {
+                println!("  - {rule}");
+            }
+        }
+        if !imported.exclude_paths.is_empty() {
+            println!("exclude_paths:");
+            for path in &imported.exclude_paths {
+                println!("  - {path}");
+            }
+        }
+    }
+
+    if !imported.unmapped.is_empty() {
+        println!();
+        println!(
+            "{}",
+            format!(
+                "{} entries have no phanalist equivalent and were not imported:",
+                imported.unmapped.len()
+            )
+            .yellow()
+        );
+        for unmapped in &imported.unmapped {
+            match &unmapped.path {
+                Some(path) => println!(
+                    "  {} ({}x) {}",
+                    path.blue(),
+                    unmapped.count,
+                    unmapped.description
+                ),
+                None => println!("  ({}x) {}", unmapped.count, unmapped.description),
+            }
+        }
+    }
+
+    process::exit(exitcode::OK);
+}
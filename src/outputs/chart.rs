@@ -1,27 +1,23 @@
 use cli_table::{format::Justify, Cell, Style, Table};
 use colored::Colorize;
 
+use crate::engineer::GroupBy;
+use crate::outputs::csv::escape;
 use crate::results::{EngineerEntry, EngineerReport, RuleChange};
 
-pub fn print_engineer_report(report: &EngineerReport, since: &Option<String>, sort_by: &str) {
+pub fn print_engineer_report(
+    report: &EngineerReport,
+    since: &Option<String>,
+    sort_by: &str,
+    group_by: GroupBy,
+) {
     if report.is_empty() {
         println!("{}", "No engineer data to report.".yellow());
         return;
     }
 
-    let mut entries: Vec<(&String, &EngineerEntry)> = report.iter().collect();
-    match sort_by {
-        "net" => entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.net)),
-        "name" => entries.sort_by_key(|(a, _)| *a),
-        "fixed" => entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.total_fixed)),
-        "introduced" => entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.total_introduced)),
-        _ => entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.total_introduced + e.total_fixed)),
-    }
-
-    let title = match since {
-        Some(s) => format!("Engineer Quality Report (since {s})"),
-        None => "Engineer Quality Report".to_string(),
-    };
+    let entries = sorted_entries(report, sort_by);
+    let title = report_title(since, group_by);
 
     println!("\n{}", title.bold().underline());
     println!();
@@ -45,12 +41,15 @@ pub fn print_engineer_report(report: &EngineerReport, since: &Option<String>, so
         ]);
     }
 
-    let table = table_rows.table().title(vec![
-        "Engineer".cell().bold(true),
-        "Fixed (✓)".cell().bold(true),
-        "Introduced (✗)".cell().bold(true),
-        "Net".cell().bold(true),
-    ]).bold(true);
+    let table = table_rows
+        .table()
+        .title(vec![
+            group_by.label().cell().bold(true),
+            "Fixed (✓)".cell().bold(true),
+            "Introduced (✗)".cell().bold(true),
+            "Net".cell().bold(true),
+        ])
+        .bold(true);
 
     println!("{}", table.display().unwrap());
     println!();
@@ -78,11 +77,154 @@ pub fn print_engineer_report(report: &EngineerReport, since: &Option<String>, so
             let desc = parts.join(", ");
             println!("    {}  {}", rule.dimmed(), desc);
         }
+        for commit in &entry.commits {
+            let sha: String = commit.sha.chars().take(10).collect();
+            let mut counts = vec![];
+            if commit.fixed > 0 {
+                counts.push(format!("-{}", commit.fixed).green().to_string());
+            }
+            if commit.introduced > 0 {
+                counts.push(format!("+{}", commit.introduced).red().to_string());
+            }
+            println!(
+                "    {}  {}  {}",
+                sha.cyan(),
+                counts.join(" "),
+                commit.message
+            );
+        }
         println!();
     }
     println!();
 }
 
+/// One row per engineer (or group) with the totals, then a fixed and an
+/// introduced column per rule.
+pub fn engineer_report_csv(report: &EngineerReport, sort_by: &str, group_by: GroupBy) -> String {
+    let rules = rules(report);
+    let label = group_by.label().to_lowercase();
+    let mut header = vec![label.as_str(), "fixed", "introduced", "net"];
+    let rule_columns: Vec<String> = rules
+        .iter()
+        .flat_map(|rule| [format!("{rule}_fixed"), format!("{rule}_introduced")])
+        .collect();
+    header.extend(rule_columns.iter().map(String::as_str));
+    let mut out = header.join(",");
+    out.push('\n');
+
+    for (author, entry) in sorted_entries(report, sort_by) {
+        let mut row = vec![
+            escape(author),
+            entry.total_fixed.to_string(),
+            entry.total_introduced.to_string(),
+            entry.net.to_string(),
+        ];
+        for rule in &rules {
+            let change = entry.rules.get(*rule).cloned().unwrap_or_default();
+            row.push(change.fixed.to_string());
+            row.push(change.introduced.to_string());
+        }
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// The report as a Markdown table for PR comments and wiki pages, with a
+/// column per rule showing what was fixed (✓) and introduced (✗).
+pub fn engineer_report_markdown(
+    report: &EngineerReport,
+    since: &Option<String>,
+    sort_by: &str,
+    group_by: GroupBy,
+) -> String {
+    let mut out = format!("## {}\n\n", report_title(since, group_by));
+    if report.is_empty() {
+        out.push_str("No engineer data to report.\n");
+        return out;
+    }
+
+    let rules = rules(report);
+    let mut header = vec![
+        group_by.label().to_string(),
+        "Fixed (✓)".to_string(),
+        "Introduced (✗)".to_string(),
+        "Net".to_string(),
+    ];
+    header.extend(rules.iter().map(|rule| rule.to_string()));
+    out.push_str(&markdown_row(&header));
+    let mut align = vec!["---".to_string()];
+    align.extend(std::iter::repeat_n("---:".to_string(), header.len() - 1));
+    out.push_str(&markdown_row(&align));
+
+    for (author, entry) in sorted_entries(report, sort_by) {
+        let net = if entry.net > 0 {
+            format!("+{}", entry.net)
+        } else {
+            entry.net.to_string()
+        };
+        let mut row = vec![
+            author.replace('|', "\\|"),
+            entry.total_fixed.to_string(),
+            entry.total_introduced.to_string(),
+            net,
+        ];
+        for rule in &rules {
+            let mut parts = vec![];
+            if let Some(change) = entry.rules.get(*rule) {
+                if change.fixed > 0 {
+                    parts.push(format!("✓{}", change.fixed));
+                }
+                if change.introduced > 0 {
+                    parts.push(format!("✗{}", change.introduced));
+                }
+            }
+            row.push(parts.join(" "));
+        }
+        out.push_str(&markdown_row(&row));
+    }
+    out
+}
+
+fn markdown_row(cells: &[String]) -> String {
+    format!("| {} |\n", cells.join(" | "))
+}
+
+/// Every rule mentioned in the report, sorted by code.
+fn rules(report: &EngineerReport) -> Vec<&String> {
+    let mut rules: Vec<&String> = report
+        .values()
+        .flat_map(|entry| entry.rules.keys())
+        .collect();
+    rules.sort();
+    rules.dedup();
+    rules
+}
+
+/// Report rows in the order requested with `--sort`: total (default), net,
+/// name, fixed or introduced.
+pub fn sorted_entries<'a>(
+    report: &'a EngineerReport,
+    sort_by: &str,
+) -> Vec<(&'a String, &'a EngineerEntry)> {
+    let mut entries: Vec<(&String, &EngineerEntry)> = report.iter().collect();
+    match sort_by {
+        "net" => entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.net)),
+        "name" => entries.sort_by_key(|(a, _)| *a),
+        "fixed" => entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.total_fixed)),
+        "introduced" => entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.total_introduced)),
+        _ => entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.total_introduced + e.total_fixed)),
+    }
+    entries
+}
+
+pub fn report_title(since: &Option<String>, group_by: GroupBy) -> String {
+    match since {
+        Some(s) => format!("{} Quality Report (since {s})", group_by.label()),
+        None => format!("{} Quality Report", group_by.label()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,21 +254,74 @@ mod tests {
     fn test_print_engineer_report_empty() {
         let report: EngineerReport = std::collections::HashMap::new();
         // Should not panic
-        print_engineer_report(&report, &None, "total");
+        print_engineer_report(&report, &None, "total", GroupBy::Engineer);
     }
 
     #[test]
     fn test_print_engineer_report_with_data() {
         let report = make_report();
         // Should not panic
-        print_engineer_report(&report, &Some("30 days".to_string()), "total");
+        print_engineer_report(
+            &report,
+            &Some("30 days".to_string()),
+            "total",
+            GroupBy::Engineer,
+        );
     }
 
     #[test]
     fn test_print_engineer_report_title_with_since() {
         // Just verify formatting doesn't crash
         let report = make_report();
-        print_engineer_report(&report, &Some("2025-01-01".to_string()), "total");
+        print_engineer_report(
+            &report,
+            &Some("2025-01-01".to_string()),
+            "total",
+            GroupBy::Engineer,
+        );
+    }
+
+    #[test]
+    fn test_report_title_names_the_grouping() {
+        assert_eq!(
+            report_title(&None, GroupBy::Engineer),
+            "Engineer Quality Report"
+        );
+        assert_eq!(
+            report_title(&Some("30 days".to_string()), GroupBy::Team),
+            "Team Quality Report (since 30 days)"
+        );
+    }
+
+    #[test]
+    fn test_engineer_report_csv_has_a_column_pair_per_rule() {
+        let csv = engineer_report_csv(&make_report(), "net", GroupBy::Engineer);
+
+        assert_eq!(
+            csv,
+            "engineer,fixed,introduced,net,E001_fixed,E001_introduced,E002_fixed,E002_introduced\n\
+             Alice,10,3,7,8,1,2,2\n\
+             Bob,1,20,-19,1,15,0,0\n"
+        );
+    }
+
+    #[test]
+    fn test_engineer_report_markdown_follows_sort_order() {
+        let markdown = engineer_report_markdown(
+            &make_report(),
+            &Some("30 days".to_string()),
+            "total",
+            GroupBy::Team,
+        );
+
+        assert_eq!(
+            markdown,
+            "## Team Quality Report (since 30 days)\n\n\
+             | Team | Fixed (✓) | Introduced (✗) | Net | E001 | E002 |\n\
+             | --- | ---: | ---: | ---: | ---: | ---: |\n\
+             | Bob | 1 | 20 | -19 | ✓1 ✗15 |  |\n\
+             | Alice | 10 | 3 | +7 | ✓8 ✗1 | ✓2 ✗2 |\n"
+        );
     }
 
 }
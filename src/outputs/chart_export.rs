@@ -0,0 +1,377 @@
+//! `--export-chart`: draw the engineer report as a bar chart, fixed against
+//! introduced violations per engineer and per rule, and save it as SVG or PNG.
+//!
+//! The chart is laid out once as a list of shapes; the SVG writer and the PNG
+//! rasterizer only translate those shapes, so both images look the same.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::engineer::GroupBy;
+use crate::outputs::chart::{report_title, sorted_entries};
+use crate::outputs::png::{Canvas, Rgb};
+use crate::results::{EngineerReport, RuleChange};
+
+const BACKGROUND: Rgb = Rgb(255, 255, 255);
+const TEXT: Rgb = Rgb(36, 41, 47);
+const MUTED: Rgb = Rgb(101, 109, 118);
+const FIXED: Rgb = Rgb(45, 164, 78);
+const INTRODUCED: Rgb = Rgb(207, 34, 46);
+
+/// Font pixels are drawn 2x2 in the PNG, giving 12x14 pixel characters; the
+/// SVG uses a 14px monospace font, which is never wider.
+const SCALE: u32 = 2;
+const TEXT_HEIGHT: u32 = 7 * SCALE;
+const MARGIN: u32 = 20;
+/// Bars are as tall as a line of text so the value labels beside them fit.
+const BAR_HEIGHT: u32 = TEXT_HEIGHT;
+const BAR_GAP: u32 = 2;
+const BAR_WIDTH: u32 = 360;
+const ROW_HEIGHT: u32 = 2 * BAR_HEIGHT + BAR_GAP + 10;
+const MAX_LABEL_CHARS: usize = 28;
+
+enum Shape {
+    Rect {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        color: Rgb,
+    },
+    /// `y` is the top of the text.
+    Text {
+        x: u32,
+        y: u32,
+        text: String,
+        color: Rgb,
+        bold: bool,
+    },
+}
+
+struct Chart {
+    width: u32,
+    height: u32,
+    shapes: Vec<Shape>,
+}
+
+/// Write the chart to `path`; the extension (`.svg` or `.png`) picks the format.
+pub fn export_chart(
+    report: &EngineerReport,
+    since: &Option<String>,
+    sort_by: &str,
+    group_by: GroupBy,
+    path: &Path,
+) -> Result<(), String> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let chart = layout(report, since, sort_by, group_by);
+    let bytes = match extension.as_str() {
+        "svg" => to_svg(&chart).into_bytes(),
+        "png" => to_png(&chart),
+        _ => {
+            return Err(format!(
+                "Unsupported chart format for {}: use a .svg or .png file",
+                path.display()
+            ))
+        }
+    };
+    std::fs::write(path, bytes)
+        .map_err(|e| format!("Failed to write chart {}: {e}", path.display()))
+}
+
+fn layout(
+    report: &EngineerReport,
+    since: &Option<String>,
+    sort_by: &str,
+    group_by: GroupBy,
+) -> Chart {
+    let engineers: Vec<(String, RuleChange)> = sorted_entries(report, sort_by)
+        .into_iter()
+        .map(|(author, entry)| {
+            let totals = RuleChange {
+                fixed: entry.total_fixed,
+                introduced: entry.total_introduced,
+            };
+            (author.clone(), totals)
+        })
+        .collect();
+
+    let mut per_rule: BTreeMap<String, RuleChange> = BTreeMap::new();
+    for entry in report.values() {
+        for (rule, change) in &entry.rules {
+            let totals = per_rule.entry(rule.clone()).or_default();
+            totals.fixed += change.fixed;
+            totals.introduced += change.introduced;
+        }
+    }
+    let rules: Vec<(String, RuleChange)> = per_rule.into_iter().collect();
+
+    let label_chars = engineers
+        .iter()
+        .chain(&rules)
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(8, MAX_LABEL_CHARS);
+    let bars_x = MARGIN + Canvas::text_width(label_chars, SCALE) + 10;
+    let title = report_title(since, group_by);
+    let width = (bars_x + BAR_WIDTH + 10 + Canvas::text_width(8, SCALE) + MARGIN)
+        .max(2 * MARGIN + Canvas::text_width(title.chars().count(), SCALE));
+
+    let mut shapes = Vec::new();
+    let mut y = MARGIN;
+    shapes.push(text(MARGIN, y, title, TEXT, true));
+    y += TEXT_HEIGHT + 12;
+
+    let mut x = MARGIN;
+    for (label, color) in [("Fixed", FIXED), ("Introduced", INTRODUCED)] {
+        shapes.push(rect(x, y, BAR_HEIGHT, BAR_HEIGHT, color));
+        shapes.push(text(x + BAR_HEIGHT + 6, y, label.to_string(), MUTED, false));
+        x += BAR_HEIGHT + 6 + Canvas::text_width(label.len() + 2, SCALE);
+    }
+    y += TEXT_HEIGHT + 16;
+
+    for (heading, rows) in [
+        (group_by.plural(), &engineers),
+        ("Per-rule breakdown", &rules),
+    ] {
+        shapes.push(text(MARGIN, y, heading.to_string(), TEXT, true));
+        y += TEXT_HEIGHT + 10;
+
+        let max = rows
+            .iter()
+            .map(|(_, c)| c.fixed.max(c.introduced))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for (label, change) in rows.iter() {
+            let label_y = y + (2 * BAR_HEIGHT + BAR_GAP - TEXT_HEIGHT) / 2;
+            shapes.push(text(
+                MARGIN,
+                label_y,
+                truncate(label, label_chars),
+                TEXT,
+                false,
+            ));
+            for (i, (value, color)) in [(change.fixed, FIXED), (change.introduced, INTRODUCED)]
+                .into_iter()
+                .enumerate()
+            {
+                let bar_y = y + i as u32 * (BAR_HEIGHT + BAR_GAP);
+                let length = (value * BAR_WIDTH as u64 / max) as u32;
+                if length > 0 {
+                    shapes.push(rect(bars_x, bar_y, length, BAR_HEIGHT, color));
+                }
+                shapes.push(text(
+                    bars_x + length + 6,
+                    bar_y,
+                    value.to_string(),
+                    MUTED,
+                    false,
+                ));
+            }
+            y += ROW_HEIGHT;
+        }
+        y += 10;
+    }
+
+    Chart {
+        width,
+        height: y + MARGIN,
+        shapes,
+    }
+}
+
+fn to_svg(chart: &Chart) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"monospace\" font-size=\"14\">\n",
+        w = chart.width,
+        h = chart.height
+    );
+    let _ = writeln!(
+        svg,
+        "  <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        hex(BACKGROUND)
+    );
+    for shape in &chart.shapes {
+        let _ = match shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => writeln!(
+                svg,
+                "  <rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" fill=\"{}\"/>",
+                hex(*color)
+            ),
+            Shape::Text {
+                x,
+                y,
+                text,
+                color,
+                bold,
+            } => writeln!(
+                svg,
+                "  <text x=\"{x}\" y=\"{}\" fill=\"{}\"{}>{}</text>",
+                y + TEXT_HEIGHT - 2,
+                hex(*color),
+                if *bold { " font-weight=\"bold\"" } else { "" },
+                escape_xml(text)
+            ),
+        };
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn to_png(chart: &Chart) -> Vec<u8> {
+    let mut canvas = Canvas::new(chart.width, chart.height, BACKGROUND);
+    for shape in &chart.shapes {
+        match shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => canvas.fill_rect(*x, *y, *width, *height, *color),
+            Shape::Text {
+                x,
+                y,
+                text,
+                color,
+                bold,
+            } => {
+                canvas.draw_text(*x, *y, text, *color, SCALE);
+                if *bold {
+                    canvas.draw_text(*x + 1, *y, text, *color, SCALE);
+                }
+            }
+        }
+    }
+    canvas.encode()
+}
+
+fn rect(x: u32, y: u32, width: u32, height: u32, color: Rgb) -> Shape {
+    Shape::Rect {
+        x,
+        y,
+        width,
+        height,
+        color,
+    }
+}
+
+fn text(x: u32, y: u32, text: String, color: Rgb, bold: bool) -> Shape {
+    Shape::Text {
+        x,
+        y,
+        text,
+        color,
+        bold,
+    }
+}
+
+fn truncate(label: &str, chars: usize) -> String {
+    if label.chars().count() <= chars {
+        label.to_string()
+    } else {
+        let mut short: String = label.chars().take(chars - 3).collect();
+        short.push_str("...");
+        short
+    }
+}
+
+fn hex(Rgb(r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::EngineerEntry;
+
+    fn report() -> EngineerReport {
+        let mut report = EngineerReport::new();
+        let mut alice = EngineerEntry {
+            total_fixed: 4,
+            total_introduced: 1,
+            net: 3,
+            ..Default::default()
+        };
+        alice.rules.insert(
+            "E0009".to_string(),
+            RuleChange {
+                fixed: 4,
+                introduced: 1,
+            },
+        );
+        report.insert("Alice <a&b>".to_string(), alice);
+        report
+    }
+
+    #[test]
+    fn svg_has_bars_per_engineer_and_rule() {
+        let svg = to_svg(&layout(
+            &report(),
+            &Some("30 days".to_string()),
+            "total",
+            GroupBy::Engineer,
+        ));
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("Engineer Quality Report (since 30 days)"));
+        assert!(svg.contains("Alice &lt;a&amp;b&gt;"));
+        assert!(svg.contains(">E0009</text>"));
+        // Fixed bar at full width, introduced bar at a quarter, in both sections.
+        assert_eq!(
+            svg.matches(&format!(
+                "width=\"{BAR_WIDTH}\" height=\"{BAR_HEIGHT}\" fill=\"#2da44e\""
+            ))
+            .count(),
+            2
+        );
+        assert_eq!(
+            svg.matches(&format!(
+                "width=\"{}\" height=\"{BAR_HEIGHT}\" fill=\"#cf222e\"",
+                BAR_WIDTH / 4
+            ))
+            .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn export_chart_picks_format_by_extension() {
+        let dir = std::env::temp_dir().join(format!("phanalist_chart_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let png = dir.join("chart.png");
+        let written = export_chart(&report(), &None, "total", GroupBy::Engineer, &png);
+        let bytes = std::fs::read(&png).unwrap_or_default();
+        let rejected = export_chart(
+            &report(),
+            &None,
+            "total",
+            GroupBy::Engineer,
+            &dir.join("chart.gif"),
+        );
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(written.is_ok());
+        assert!(bytes.starts_with(b"\x89PNG"));
+        assert!(rejected.is_err());
+    }
+}
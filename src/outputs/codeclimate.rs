@@ -17,6 +17,11 @@ impl OutputFormatter for CodeClimate {
         for (key, violations) in &results.files {
             for violation in violations {
                 let rule_id = &violation.rule;
+                let (category, severity) = if violation.is_error() {
+                    ("Bug Risk", "blocker")
+                } else {
+                    ("Complexity", "major")
+                };
                 let rule_markdown = match rules.get(rule_id) {
                     Some(rule) => rule.get_detailed_explanation().unwrap_or_default(),
                     None => String::from("Unknown rule"),
@@ -29,9 +34,9 @@ impl OutputFormatter for CodeClimate {
                     "content": {
                         "body": &rule_markdown
                     },
-                    "categories": ["Complexity"],
+                    "categories": [category],
                     "fingerprint": "",
-                    "severity": "major",
+                    "severity": severity,
                     "location": {
                         "path": &key,
                         "positions": {
@@ -0,0 +1,55 @@
+use crate::results::Results;
+
+use super::OutputFormatter;
+
+pub struct Csv {}
+
+impl OutputFormatter for Csv {
+    /// One row per violation, sorted by path and position.
+    fn output(results: &mut Results) {
+        println!("path,rule,id,start_line,start_column,end_line,end_column,symbol,message");
+
+        let mut paths: Vec<&String> = results.files.keys().collect();
+        paths.sort();
+        for path in paths {
+            let mut violations: Vec<_> = results.files[path].iter().collect();
+            violations.sort_by_key(|v| (v.start_line, v.start_column, v.rule.clone()));
+            for violation in violations {
+                let row = [
+                    escape(path),
+                    escape(&violation.rule),
+                    escape(&violation.message.id),
+                    violation.start_line.to_string(),
+                    violation.start_column.to_string(),
+                    violation.end_line.to_string(),
+                    violation.end_column.to_string(),
+                    escape(violation.symbol.as_deref().unwrap_or_default()),
+                    escape(&violation.message.render()),
+                ];
+                println!("{}", row.join(","));
+            }
+        }
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+/// (RFC 4180).
+pub fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_quotes_only_when_needed() {
+        assert_eq!(escape("E0001"), "E0001");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
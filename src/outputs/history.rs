@@ -0,0 +1,168 @@
+use std::collections::BTreeSet;
+
+use cli_table::{format::Justify, Cell, Style, Table};
+use colored::Colorize;
+
+use crate::history::History;
+use crate::outputs::csv::escape;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub fn print_history(history: &History) {
+    let (Some(first), Some(last)) = (history.snapshots.first(), history.snapshots.last()) else {
+        println!("{}", "No commits to report.".yellow());
+        return;
+    };
+
+    let title = format!(
+        "Quality History ({}, {} to {}, {} snapshots)",
+        history.interval,
+        first.date,
+        last.date,
+        history.snapshots.len()
+    );
+    println!("\n{}", title.bold().underline());
+    println!();
+
+    let mut table_rows = vec![];
+    for rule in rules(history) {
+        let series: Vec<i64> = history
+            .snapshots
+            .iter()
+            .map(|s| s.codes_count.get(&rule).copied().unwrap_or(0))
+            .collect();
+        table_rows.push(row(rule.as_str(), &series));
+    }
+    let totals: Vec<i64> = history.snapshots.iter().map(|s| s.total).collect();
+    table_rows.push(row("Total", &totals));
+
+    let table = table_rows
+        .table()
+        .title(vec![
+            "Rule".cell().bold(true),
+            "Trend".cell().bold(true),
+            first.date.as_str().cell().bold(true),
+            last.date.as_str().cell().bold(true),
+            "Change".cell().bold(true),
+        ])
+        .bold(true);
+
+    println!("{}", table.display().unwrap());
+    println!();
+}
+
+/// One row per snapshot with a column per rule seen anywhere in the history.
+pub fn history_csv(history: &History) -> String {
+    let rules = rules(history);
+    let mut header = vec!["commit", "date", "files", "total"];
+    header.extend(rules.iter().map(String::as_str));
+    let mut out = header.join(",");
+    out.push('\n');
+
+    for snapshot in &history.snapshots {
+        let mut row = vec![
+            escape(&snapshot.commit),
+            escape(&snapshot.date),
+            snapshot.files.to_string(),
+            snapshot.total.to_string(),
+        ];
+        for rule in &rules {
+            row.push(snapshot.codes_count.get(rule).copied().unwrap_or(0).to_string());
+        }
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Scale `series` onto eight block characters, from its minimum to its maximum.
+pub fn sparkline(series: &[i64]) -> String {
+    let min = series.iter().copied().min().unwrap_or(0);
+    let max = series.iter().copied().max().unwrap_or(0);
+    series
+        .iter()
+        .map(|value| {
+            if max == min {
+                SPARKS[0]
+            } else {
+                SPARKS[((value - min) * (SPARKS.len() as i64 - 1) / (max - min)) as usize]
+            }
+        })
+        .collect()
+}
+
+fn rules(history: &History) -> Vec<String> {
+    let rules: BTreeSet<&String> = history
+        .snapshots
+        .iter()
+        .flat_map(|s| s.codes_count.keys())
+        .collect();
+    rules.into_iter().cloned().collect()
+}
+
+fn row(label: &str, series: &[i64]) -> Vec<cli_table::CellStruct> {
+    let first = series.first().copied().unwrap_or(0);
+    let last = series.last().copied().unwrap_or(0);
+    let change = last - first;
+    // Fewer violations is an improvement.
+    let change_str = if change < 0 {
+        change.to_string().green().to_string()
+    } else if change > 0 {
+        format!("+{change}").red().to_string()
+    } else {
+        "0".to_string()
+    };
+
+    vec![
+        label.cell(),
+        sparkline(series).cell(),
+        first.cell().justify(Justify::Right),
+        last.cell().justify(Justify::Right),
+        change_str.cell().justify(Justify::Right),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::history::Snapshot;
+
+    fn snapshot(commit: &str, counts: &[(&str, i64)]) -> Snapshot {
+        let codes_count: BTreeMap<String, i64> =
+            counts.iter().map(|(r, c)| (r.to_string(), *c)).collect();
+        Snapshot {
+            commit: commit.to_string(),
+            date: "2026-01-01".to_string(),
+            files: 3,
+            total: codes_count.values().sum(),
+            codes_count,
+        }
+    }
+
+    #[test]
+    fn sparkline_scales_between_min_and_max() {
+        assert_eq!(sparkline(&[0, 7, 14]), "▁▄█");
+        assert_eq!(sparkline(&[5, 5]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn csv_has_a_column_per_rule() {
+        let history = History {
+            interval: "weekly".to_string(),
+            snapshots: vec![
+                snapshot("aaa", &[("E0001", 2)]),
+                snapshot("bbb", &[("E0001", 1), ("E0009", 4)]),
+            ],
+        };
+
+        assert_eq!(
+            history_csv(&history),
+            "commit,date,files,total,E0001,E0009\n\
+             aaa,2026-01-01,3,2,2,0\n\
+             bbb,2026-01-01,3,5,1,4\n"
+        );
+    }
+}
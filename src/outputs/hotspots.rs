@@ -0,0 +1,166 @@
+use std::fmt::Write as _;
+
+use cli_table::{format::Justify, Cell, Style, Table};
+use colored::Colorize;
+
+use crate::hotspots::Hotspots;
+use crate::outputs::chart_export::escape_xml;
+
+/// Rows shown in the terminal; JSON and HTML list every hotspot.
+const TEXT_LIMIT: usize = 25;
+
+pub fn print_hotspots(report: &Hotspots) {
+    if report.hotspots.is_empty() {
+        println!(
+            "{}",
+            "No hotspots: nothing below the sources changed in the window.".yellow()
+        );
+        return;
+    }
+
+    println!("\n{}", title(report).bold().underline());
+    println!();
+
+    let table_rows: Vec<_> = report
+        .hotspots
+        .iter()
+        .take(TEXT_LIMIT)
+        .enumerate()
+        .map(|(i, h)| {
+            vec![
+                (i + 1).cell().justify(Justify::Right),
+                h.name.as_str().cell(),
+                h.commits.cell().justify(Justify::Right),
+                h.cyclomatic.cell().justify(Justify::Right),
+                h.cognitive.cell().justify(Justify::Right),
+                h.lines.cell().justify(Justify::Right),
+                h.score
+                    .to_string()
+                    .red()
+                    .to_string()
+                    .cell()
+                    .justify(Justify::Right),
+            ]
+        })
+        .collect();
+
+    let table = table_rows
+        .table()
+        .title(vec![
+            "#".cell().bold(true),
+            "Name".cell().bold(true),
+            "Commits".cell().bold(true),
+            "Cyclomatic".cell().bold(true),
+            "Cognitive".cell().bold(true),
+            "Lines".cell().bold(true),
+            "Score".cell().bold(true),
+        ])
+        .bold(true);
+
+    println!("{}", table.display().unwrap());
+    if report.hotspots.len() > TEXT_LIMIT {
+        println!(
+            "{}",
+            format!(
+                "... and {} more; use --output-format json or html for the full list.",
+                report.hotspots.len() - TEXT_LIMIT
+            )
+            .dimmed()
+        );
+    }
+    println!();
+}
+
+/// A standalone HTML page with the ranked hotspots and a bar per score.
+pub fn hotspots_html(report: &Hotspots) -> String {
+    let max = report
+        .hotspots
+        .iter()
+        .map(|h| h.score)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let title = escape_xml(&title(report));
+
+    let mut html = format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, \"Segoe UI\", Helvetica, Arial, sans-serif; color: #24292f; margin: 2rem; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ padding: 4px 8px; border-bottom: 1px solid #d0d7de; text-align: right; }}
+th.name, td.name {{ text-align: left; font-family: monospace; }}
+td.bar {{ width: 30%; text-align: left; }}
+td.bar div {{ background: #cf222e; height: 12px; }}
+small {{ color: #656d76; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<table>
+<tr><th>#</th><th class=\"name\">Name</th><th>Commits</th><th>Cyclomatic</th><th>Cognitive</th><th>Lines</th><th>Score</th><th></th></tr>
+"
+    );
+    for (i, h) in report.hotspots.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"name\">{}<br><small>{}:{}</small></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"bar\"><div style=\"width: {:.1}%\"></div></td></tr>",
+            i + 1,
+            escape_xml(&h.name),
+            escape_xml(&h.file),
+            h.line,
+            h.commits,
+            h.cyclomatic,
+            h.cognitive,
+            h.lines,
+            h.score,
+            h.score as f64 * 100.0 / max as f64
+        );
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+fn title(report: &Hotspots) -> String {
+    format!(
+        "Hotspots by {}: commits x {} ({} commits)",
+        report.level, report.metric, report.commits
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotspots::Hotspot;
+
+    #[test]
+    fn html_lists_every_hotspot_with_a_scaled_bar() {
+        let hotspot = |name: &str, score| Hotspot {
+            name: name.to_string(),
+            file: "src/A.php".to_string(),
+            line: 3,
+            commits: 2,
+            score,
+            ..Default::default()
+        };
+        let report = Hotspots {
+            level: "method".to_string(),
+            metric: "cognitive complexity".to_string(),
+            commits: 4,
+            hotspots: vec![hotspot("App\\A::<busy>", 10), hotspot("App\\A::simple", 5)],
+        };
+
+        let html = hotspots_html(&report);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(
+            "<title>Hotspots by method: commits x cognitive complexity (4 commits)</title>"
+        ));
+        assert!(html.contains("App\\A::&lt;busy&gt;<br><small>src/A.php:3</small>"));
+        assert!(html.contains("width: 100.0%"));
+        assert!(html.contains("width: 50.0%"));
+    }
+}
@@ -3,8 +3,14 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 pub mod chart;
+pub mod chart_export;
 pub mod codeclimate;
+pub mod csv;
+pub mod history;
+pub mod hotspots;
 pub mod json;
+pub mod ndjson;
+pub mod png;
 pub mod sarif;
 pub mod text;
 
@@ -19,6 +25,13 @@ pub enum Format {
     json,
     sarif,
     codeclimate,
+    csv,
+    /// One JSON violation per line, streamed while scanning.
+    ndjson,
+    /// Only for reports rendered as a page, such as `--hotspots`.
+    html,
+    /// Only for the `--blame` engineer report.
+    markdown,
 }
 
 impl FromStr for Format {
@@ -30,6 +43,10 @@ impl FromStr for Format {
             "json" => Ok(Format::json),
             "sarif" => Ok(Format::sarif),
             "codeclimate" => Ok(Format::codeclimate),
+            "csv" => Ok(Format::csv),
+            "ndjson" => Ok(Format::ndjson),
+            "html" => Ok(Format::html),
+            "markdown" => Ok(Format::markdown),
             _ => Err(()),
         }
     }
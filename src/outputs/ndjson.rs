@@ -0,0 +1,80 @@
+use std::io::{self, Write};
+
+use crate::results::{Results, Violation};
+
+use super::OutputFormatter;
+
+/// Newline-delimited JSON: one violation per line, with its `file`. Lines can
+/// be written as each file is analysed, so a scan never holds all of them.
+pub struct NdJson {}
+
+impl NdJson {
+    /// Write a line for each violation of `path`.
+    pub fn write_file(
+        out: &mut impl Write,
+        path: &str,
+        violations: &[Violation],
+    ) -> io::Result<()> {
+        for violation in violations {
+            let mut line = serde_json::to_value(violation)?;
+            line["file"] = path.into();
+            writeln!(out, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl OutputFormatter for NdJson {
+    fn output(results: &mut Results) {
+        let mut out = io::stdout().lock();
+        let mut paths: Vec<&String> = results.files.keys().collect();
+        paths.sort();
+        for path in paths {
+            if NdJson::write_file(&mut out, path, &results.files[path]).is_err() {
+                // Nobody is reading anymore, e.g. piped to `head`.
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::results::Message;
+
+    use super::*;
+
+    #[test]
+    fn one_line_per_violation() {
+        let violation = Violation {
+            rule: "E0011".to_string(),
+            line: "$f = @fopen($path);".to_string(),
+            message: Message::new(
+                "E0011:error-suppression",
+                "Error supression(@) symbol found. Remove it.",
+            ),
+            start_line: 3,
+            start_column: 5,
+            end_line: 3,
+            end_column: 18,
+            symbol: None,
+            baseline_state: None,
+            inline_suppressed: false,
+        };
+
+        let mut out = Vec::new();
+        NdJson::write_file(&mut out, "src/Log.php", &[violation.clone(), violation]).unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["file"], "src/Log.php");
+        assert_eq!(lines[0]["rule"], "E0011");
+        assert_eq!(lines[0]["start_line"], 3);
+    }
+}
@@ -1,14 +1,24 @@
 use serde_sarif::sarif::{
     self, ArtifactLocation, Message, MultiformatMessageString, PhysicalLocation, Run,
-    Sarif as StandardSarif, Tool, ToolComponent,
+    Sarif as StandardSarif, Suppression, Tool, ToolComponent,
 };
 
-use crate::{results::Results, rules};
+use crate::{
+    results::{BaselineState, Results},
+    rules,
+};
 
 use super::OutputFormatter;
 pub struct Sarif {}
 impl OutputFormatter for Sarif {
     fn output(results: &mut Results) {
+        let message = serde_json::json!(Sarif::build(results));
+        println!("{}", message);
+    }
+}
+
+impl Sarif {
+    fn build(results: &Results) -> StandardSarif {
         let version: &str = env!("CARGO_PKG_VERSION");
         let description = MultiformatMessageString {
             markdown: None,
@@ -114,12 +124,54 @@ impl OutputFormatter for Sarif {
                     start_line: Some((violation.start_line as i64).max(1)),
                 };
 
+                // Absent results come from the baseline and have no position left.
+                let region = match violation.baseline_state {
+                    Some(BaselineState::Absent) => None,
+                    _ => Some(region),
+                };
+
                 let physical_location = PhysicalLocation {
                     address: None,
                     artifact_location: Some(analysis_target.clone()),
                     context_region: None,
                     properties: None,
-                    region: Some(region.clone()),
+                    region,
+                };
+
+                let baseline_state = violation.baseline_state.map(|state| {
+                    serde_json::Value::String(
+                        match state {
+                            BaselineState::New => "new",
+                            BaselineState::Unchanged => "unchanged",
+                            BaselineState::Absent => "absent",
+                        }
+                        .to_string(),
+                    )
+                });
+
+                let suppression = |kind: &str, justification: &str| {
+                    Some(vec![Suppression {
+                        guid: None,
+                        justification: Some(String::from(justification)),
+                        kind: serde_json::Value::String(String::from(kind)),
+                        location: None,
+                        properties: None,
+                        status: None,
+                    }])
+                };
+                // Absent results are suppressed too, so that consumers ignoring
+                // `baselineState` do not show fixed violations as open alerts.
+                let suppressions = match violation.baseline_state {
+                    _ if violation.inline_suppressed => {
+                        suppression("inSource", "Silenced by a @phanalist-ignore comment")
+                    }
+                    Some(BaselineState::Unchanged) => {
+                        suppression("external", "Accounted for in the baseline")
+                    }
+                    Some(BaselineState::Absent) => {
+                        suppression("external", "Fixed since the baseline was written")
+                    }
+                    _ => None,
                 };
 
                 let location = sarif::Location {
@@ -135,7 +187,7 @@ impl OutputFormatter for Sarif {
                 t.push(serde_sarif::sarif::Result {
                     analysis_target: Some(analysis_target),
                     attachments: None,
-                    baseline_state: None,
+                    baseline_state,
                     code_flows: None,
                     correlation_guid: None,
                     fingerprints: None,
@@ -145,7 +197,13 @@ impl OutputFormatter for Sarif {
                     guid: None,
                     hosted_viewer_uri: None,
                     kind: Some(serde_json::Value::String(String::from("fail"))),
-                    level: Some(serde_json::Value::String(String::from("warning"))),
+                    level: Some(serde_json::Value::String(String::from(
+                        if violation.is_error() {
+                            "error"
+                        } else {
+                            "warning"
+                        },
+                    ))),
                     locations: Some(vec![location]),
                     message,
                     occurrence_count: None,
@@ -158,7 +216,7 @@ impl OutputFormatter for Sarif {
                     rule_id: Some(violation.rule.clone()),
                     rule_index: rule_to_index.get(&violation.rule).copied(),
                     stacks: None,
-                    suppressions: None,
+                    suppressions,
                     taxa: None,
                     web_request: None,
                     web_response: None,
@@ -224,14 +282,86 @@ impl OutputFormatter for Sarif {
             web_requests: None,
             web_responses: None,
         }];
-        let s = StandardSarif {
+        StandardSarif {
             schema: Some(String::from("https://json.schemastore.org/sarif-2.1.0")),
             inline_external_properties: None,
             properties: None,
             runs,
             version: serde_json::Value::String("2.1.0".to_string()),
-        };
-        let message = serde_json::json!(s);
-        println!("{}", message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::results::{Message, Violation};
+
+    use super::*;
+
+    fn violation(start_line: usize) -> Violation {
+        Violation {
+            rule: "E0011".to_string(),
+            line: String::new(),
+            message: Message::new("E0011:error-suppression", "Remove it."),
+            start_line,
+            start_column: 5,
+            end_line: start_line,
+            end_column: 18,
+            symbol: None,
+            baseline_state: None,
+            inline_suppressed: false,
+        }
+    }
+
+    #[test]
+    fn suppressed_results_carry_their_suppression() {
+        let mut results = Results::default();
+        results.files.insert(
+            "./src/Log.php".to_string(),
+            vec![
+                Violation {
+                    baseline_state: Some(BaselineState::New),
+                    ..violation(2)
+                },
+                Violation {
+                    baseline_state: Some(BaselineState::Unchanged),
+                    ..violation(3)
+                },
+                Violation {
+                    baseline_state: Some(BaselineState::Absent),
+                    ..violation(0)
+                },
+                Violation {
+                    inline_suppressed: true,
+                    ..violation(4)
+                },
+            ],
+        );
+
+        let sarif = json!(Sarif::build(&results));
+
+        let shape: Vec<(Value, Value, Value)> = sarif["runs"][0]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| {
+                (
+                    result["baselineState"].clone(),
+                    result["suppressions"][0]["kind"].clone(),
+                    result["locations"][0]["physicalLocation"]["region"]["startLine"].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                (json!("new"), Value::Null, json!(2)),
+                (json!("unchanged"), json!("external"), json!(3)),
+                (json!("absent"), json!("external"), Value::Null),
+                (Value::Null, json!("inSource"), json!(4)),
+            ]
+        );
     }
 }
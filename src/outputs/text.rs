@@ -14,6 +14,7 @@ impl OutputFormatter for Text {
     fn output(results: &mut Results) {
         Self::output_files_with_violations(results);
         Self::output_summary(results);
+        Self::output_baseline_fixed(results);
 
         let memory_usage = if let Some(usage) = memory_stats() {
             human_bytes(usage.physical_mem as f64)
@@ -60,6 +61,39 @@ impl Text {
         }
     }
 
+    fn output_baseline_fixed(results: &Results) {
+        if results.baseline_fixed.is_empty() {
+            return;
+        }
+
+        let fixed: usize = results.baseline_fixed.iter().map(|f| f.fixed()).sum();
+        println!(
+            "{}",
+            format!(
+                "{fixed} baselined violations are fixed, remove them from the baseline (--prune-baseline):"
+            )
+            .green()
+            .bold()
+        );
+        for fixed in &results.baseline_fixed {
+            let entry = &fixed.entry;
+            let path = if entry.path.is_empty() {
+                entry.file.clone()
+            } else {
+                format!("{}/{}", entry.path, entry.file)
+            };
+            println!(
+                "  {}\t{} {} (baseline {}, found {})",
+                entry.rule.yellow().bold(),
+                path.blue(),
+                entry.id.dimmed(),
+                entry.count,
+                fixed.found
+            );
+        }
+        println!();
+    }
+
     fn output_summary(results: &Results) {
         let all_rules = rules::all_rules();
         let mut rows = vec![];
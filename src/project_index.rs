@@ -0,0 +1,738 @@
+//! Project-wide index of the classes, interfaces, traits and enums in a scan.
+//!
+//! Built once from every parsed file before the rules run and shared
+//! read-only through [`File::project`], so cross-file rules neither walk every
+//! AST again nor keep registries of their own. Lookups fall back to the PHP
+//! core and extension declarations of `src/stubs/php.php`.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use mago_allocator::prelude::LocalArena;
+use mago_span::HasSpan;
+use mago_syntax::cst::*;
+
+use crate::file::{is_builtin_type, File, SymbolKind};
+use crate::rules::guard;
+use crate::rules::visitor::{self, Scope, Visitor};
+
+/// Classes and functions built into PHP and its common extensions. Built
+/// without a deadline: the first lookup may come from a rule short of time,
+/// and a build stopped halfway would poison the lock for every later file.
+static BUILTINS: LazyLock<ProjectIndex> = LazyLock::new(|| {
+    guard::without_deadline(|| {
+        let arena = LocalArena::new();
+        let file = File::new(
+            &arena,
+            PathBuf::from("stubs/php.php"),
+            include_str!("stubs/php.php").to_string(),
+        );
+        ProjectIndex::build([&file])
+    })
+});
+
+#[derive(Debug, Clone, Default)]
+pub struct ProjectIndex {
+    /// Class-likes by lowercase fully qualified name, as PHP class names are
+    /// case-insensitive; each keeps the name as declared.
+    class_likes: HashMap<String, ClassLike>,
+    /// Functions by lowercase fully qualified name, with their file.
+    functions: HashMap<String, (PathBuf, MethodSignature)>,
+    /// Lowercase parent class → classes extending it.
+    subclasses: HashMap<String, BTreeSet<String>>,
+}
+
+/// A class-like declaration. Class names are fully qualified, and types are
+/// rendered as declared with their class names resolved, e.g. `?App\Model\User`.
+#[derive(Debug, Clone)]
+pub struct ClassLike {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub path: PathBuf,
+    /// Declared `abstract`; interfaces are not.
+    pub is_abstract: bool,
+    /// Extended class.
+    pub parent: Option<String>,
+    /// Implemented interfaces, or the extended ones of an interface.
+    pub interfaces: Vec<String>,
+    pub traits: Vec<String>,
+    /// Methods by lowercased name; the signature keeps the declared spelling.
+    pub methods: HashMap<String, MethodSignature>,
+    /// Property types by name, without `$`.
+    pub properties: HashMap<String, Option<String>>,
+    /// Constants, and the cases of an enum.
+    pub constants: Vec<String>,
+    /// Every other class named in the declaration, its signatures or its bodies.
+    pub references: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodSignature {
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    /// Name without `$`.
+    pub name: String,
+    pub hint: Option<String>,
+}
+
+impl ProjectIndex {
+    pub fn build<'a, 'arena: 'a>(files: impl IntoIterator<Item = &'a File<'arena>>) -> Self {
+        let mut index = Self::default();
+        for file in files {
+            index.add_file(file);
+        }
+        index
+    }
+
+    /// Index every class-like declared in `file`, replacing earlier entries
+    /// with the same name.
+    pub fn add_file(&mut self, file: &File<'_>) {
+        let Some(program) = file.ast else {
+            return;
+        };
+        for statement in program.statements.iter() {
+            match statement {
+                Statement::Namespace(ns) => {
+                    for statement in ns.statements().iter() {
+                        self.add_statement(file, statement);
+                    }
+                }
+                _ => self.add_statement(file, statement),
+            }
+        }
+    }
+
+    /// Forget the class-likes indexed from `path`, before re-indexing a changed file.
+    pub fn remove_file(&mut self, path: &Path) {
+        let removed: Vec<String> = self
+            .class_likes
+            .values()
+            .filter(|class_like| class_like.path == path)
+            .map(|class_like| key(&class_like.name))
+            .collect();
+        for name in removed {
+            if let Some(class_like) = self.class_likes.remove(&name) {
+                self.unlink(&class_like);
+            }
+        }
+        self.functions
+            .retain(|_, (function_path, _)| function_path != path);
+    }
+
+    /// The class-like declared as `name` in the project, else in PHP itself.
+    /// Class names are case-insensitive.
+    pub fn get(&self, name: &str) -> Option<&ClassLike> {
+        let name = key(name);
+        self.class_likes
+            .get(&name)
+            .or_else(|| BUILTINS.class_likes.get(&name))
+    }
+
+    /// The function declared as `name` in the project, else in PHP itself.
+    /// Function names are case-insensitive.
+    pub fn function(&self, name: &str) -> Option<&MethodSignature> {
+        let name = key(name);
+        self.functions
+            .get(&name)
+            .or_else(|| BUILTINS.functions.get(&name))
+            .map(|(_, signature)| signature)
+    }
+
+    /// The class-likes declared in the project, without PHP's own.
+    pub fn class_likes(&self) -> impl Iterator<Item = &ClassLike> {
+        self.class_likes.values()
+    }
+
+    /// Classes directly extending `name`.
+    pub fn subclasses(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.subclasses
+            .get(&key(name))
+            .into_iter()
+            .flat_map(|children| children.iter().map(String::as_str))
+    }
+
+    /// Signature of `method` declared on `name`, one of its traits or ancestors.
+    pub fn find_method(&self, name: &str, method: &str) -> Option<&MethodSignature> {
+        let mut pending = vec![name];
+        let mut seen = BTreeSet::new();
+        while let Some(name) = pending.pop() {
+            if !seen.insert(name) {
+                continue;
+            }
+            let Some(class_like) = self.get(name) else {
+                continue;
+            };
+            if let Some(signature) = class_like.methods.get(&key(method)) {
+                return Some(signature);
+            }
+            pending.extend(class_like.interfaces.iter().rev().map(String::as_str));
+            pending.extend(class_like.parent.as_deref());
+            pending.extend(class_like.traits.iter().rev().map(String::as_str));
+        }
+        None
+    }
+
+    /// Afferent and efferent coupling (Ca, Ce) of `namespace`: the classes
+    /// outside it that reference its class-likes, and the class-likes outside
+    /// it that its classes reference.
+    pub fn namespace_coupling(&self, namespace: &str) -> (usize, usize) {
+        let in_namespace = |name: &str| {
+            self.get(name)
+                .is_some_and(|class_like| class_like.namespace.as_deref() == Some(namespace))
+        };
+
+        let mut afferent: BTreeSet<&str> = BTreeSet::new();
+        let mut efferent: BTreeSet<&str> = BTreeSet::new();
+        for class in self.class_likes().filter(|c| c.kind == SymbolKind::Class) {
+            if class.namespace.as_deref() == Some(namespace) {
+                efferent.extend(
+                    class
+                        .references
+                        .iter()
+                        .map(String::as_str)
+                        .filter(|name| !in_namespace(name)),
+                );
+            } else if class.references.iter().any(|name| in_namespace(name)) {
+                afferent.insert(&class.name);
+            }
+        }
+
+        (afferent.len(), efferent.len())
+    }
+
+    fn add_statement(&mut self, file: &File<'_>, statement: &Statement<'_>) {
+        if let Statement::Function(function) = statement {
+            let offset = function.span().start.offset;
+            let name = String::from_utf8_lossy(function.name.value);
+            let name = match file
+                .name_scope(offset)
+                .and_then(|scope| scope.namespace.as_ref())
+            {
+                Some(namespace) => format!("{namespace}\\{name}"),
+                None => name.into_owned(),
+            };
+            let signature = MethodSignature::new(
+                file,
+                name,
+                &function.parameter_list,
+                function.return_type_hint.as_ref(),
+            );
+            self.functions
+                .insert(key(&signature.name), (file.path.clone(), signature));
+            return;
+        }
+
+        let Some(class_like) = ClassLike::from_statement(file, statement) else {
+            return;
+        };
+        let name = key(&class_like.name);
+        if let Some(previous) = self.class_likes.remove(&name) {
+            self.unlink(&previous);
+        }
+        if let Some(parent) = &class_like.parent {
+            self.subclasses
+                .entry(key(parent))
+                .or_default()
+                .insert(class_like.name.clone());
+        }
+        self.class_likes.insert(name, class_like);
+    }
+
+    fn unlink(&mut self, class_like: &ClassLike) {
+        if let Some(children) = class_like
+            .parent
+            .as_ref()
+            .and_then(|parent| self.subclasses.get_mut(&key(parent)))
+        {
+            children.remove(&class_like.name);
+        }
+    }
+}
+
+impl ClassLike {
+    fn from_statement(file: &File<'_>, statement: &Statement<'_>) -> Option<Self> {
+        let (kind, name, members, node) = match statement {
+            Statement::Class(class) => (
+                SymbolKind::Class,
+                class.name.value,
+                &class.members,
+                Node::Class(class),
+            ),
+            Statement::Interface(interface) => (
+                SymbolKind::Interface,
+                interface.name.value,
+                &interface.members,
+                Node::Interface(interface),
+            ),
+            Statement::Trait(t) => (SymbolKind::Trait, t.name.value, &t.members, Node::Trait(t)),
+            Statement::Enum(e) => (SymbolKind::Enum, e.name.value, &e.members, Node::Enum(e)),
+            _ => return None,
+        };
+
+        let offset = statement.span().start.offset;
+        let resolve = |identifier: &Identifier<'_>| {
+            file.resolve_class_name(&String::from_utf8_lossy(identifier.value()), offset)
+        };
+        let resolve_all = |identifiers: Option<&TokenSeparatedSequence<'_, Identifier<'_>>>| {
+            identifiers
+                .map(|identifiers| identifiers.iter().map(resolve).collect())
+                .unwrap_or_default()
+        };
+
+        let mut class_like = ClassLike {
+            kind,
+            name: file.resolve_class_name(&String::from_utf8_lossy(name), offset),
+            namespace: file
+                .name_scope(offset)
+                .and_then(|scope| scope.namespace.clone()),
+            path: file.path.clone(),
+            is_abstract: false,
+            parent: None,
+            interfaces: Vec::new(),
+            traits: Vec::new(),
+            methods: HashMap::new(),
+            properties: HashMap::new(),
+            constants: Vec::new(),
+            references: BTreeSet::new(),
+        };
+        match statement {
+            Statement::Class(class) => {
+                class_like.is_abstract = class
+                    .modifiers
+                    .iter()
+                    .any(|m| matches!(m, Modifier::Abstract(_)));
+                class_like.parent = class
+                    .extends
+                    .as_ref()
+                    .and_then(|extends| extends.types.iter().next())
+                    .map(resolve);
+                class_like.interfaces = resolve_all(class.implements.as_ref().map(|i| &i.types));
+            }
+            Statement::Interface(interface) => {
+                class_like.interfaces = resolve_all(interface.extends.as_ref().map(|e| &e.types));
+            }
+            Statement::Enum(e) => {
+                class_like.interfaces = resolve_all(e.implements.as_ref().map(|i| &i.types));
+            }
+            _ => {}
+        }
+
+        for member in members.iter() {
+            match member {
+                ClassLikeMember::TraitUse(trait_use) => {
+                    class_like
+                        .traits
+                        .extend(trait_use.trait_names.iter().map(resolve));
+                }
+                ClassLikeMember::Constant(constant) => {
+                    class_like.constants.extend(
+                        constant
+                            .items
+                            .iter()
+                            .map(|item| String::from_utf8_lossy(item.name.value).into_owned()),
+                    );
+                }
+                ClassLikeMember::EnumCase(case) => {
+                    class_like
+                        .constants
+                        .push(String::from_utf8_lossy(case.item.name().value).into_owned());
+                }
+                ClassLikeMember::Property(property) => {
+                    let hint = property.hint().map(|hint| render_hint(file, hint));
+                    for variable in property.variables() {
+                        let name = String::from_utf8_lossy(variable.name);
+                        class_like
+                            .properties
+                            .insert(name.trim_start_matches('$').to_string(), hint.clone());
+                    }
+                }
+                ClassLikeMember::Method(method) => {
+                    let name = String::from_utf8_lossy(method.name.value).into_owned();
+                    class_like.methods.insert(
+                        key(&name),
+                        MethodSignature::new(
+                            file,
+                            name,
+                            &method.parameter_list,
+                            method.return_type_hint.as_ref(),
+                        ),
+                    );
+                }
+            }
+        }
+
+        // Named once each, whatever the spelling.
+        let mut seen = HashSet::from([key(&class_like.name)]);
+        class_like.references = referenced_classes(node)
+            .iter()
+            .map(|identifier| resolve(identifier))
+            .filter(|name| !is_builtin_type(name) && seen.insert(key(name)))
+            .collect();
+
+        Some(class_like)
+    }
+}
+
+impl MethodSignature {
+    fn new(
+        file: &File<'_>,
+        name: String,
+        parameter_list: &FunctionLikeParameterList<'_>,
+        return_type_hint: Option<&FunctionLikeReturnTypeHint<'_>>,
+    ) -> Self {
+        let parameters = parameter_list
+            .parameters
+            .iter()
+            .map(|parameter| Parameter {
+                name: String::from_utf8_lossy(parameter.variable.name)
+                    .trim_start_matches('$')
+                    .to_string(),
+                hint: parameter.hint.as_ref().map(|hint| render_hint(file, hint)),
+            })
+            .collect();
+        MethodSignature {
+            name,
+            parameters,
+            return_type: return_type_hint.map(|hint| render_hint(file, &hint.hint)),
+        }
+    }
+}
+
+/// Index key of the class, function or method `name`.
+fn key(name: &str) -> String {
+    name.to_ascii_lowercase()
+}
+
+/// Identifiers naming a class anywhere under `node`: parents, hints,
+/// instantiations, static accesses and `instanceof` operands.
+fn referenced_classes<'ast, 'arena>(node: Node<'ast, 'arena>) -> Vec<&'ast Identifier<'arena>> {
+    let mut references = References::default();
+    visitor::walk(node, &mut references);
+    references.0
+}
+
+#[derive(Default)]
+struct References<'ast, 'arena>(Vec<&'ast Identifier<'arena>>);
+
+impl<'ast, 'arena> Visitor<'ast, 'arena> for References<'ast, 'arena> {
+    fn enter(&mut self, node: Node<'ast, 'arena>, _scope: &Scope) -> bool {
+        let class_identifier = |expression: &'ast Expression<'arena>| match expression {
+            Expression::Identifier(identifier) => Some(identifier),
+            _ => None,
+        };
+
+        let identifiers = &mut self.0;
+        match node {
+            Node::Extends(extends) => identifiers.extend(extends.types.iter()),
+            Node::Implements(implements) => identifiers.extend(implements.types.iter()),
+            Node::TraitUse(trait_use) => identifiers.extend(trait_use.trait_names.iter()),
+            Node::Hint(Hint::Identifier(identifier)) => identifiers.push(identifier),
+            Node::Instantiation(instantiation) => {
+                identifiers.extend(class_identifier(instantiation.class))
+            }
+            Node::StaticMethodCall(call) => identifiers.extend(class_identifier(call.class)),
+            Node::StaticMethodPartialApplication(partial) => {
+                identifiers.extend(class_identifier(partial.class))
+            }
+            Node::StaticPropertyAccess(access) => {
+                identifiers.extend(class_identifier(access.class))
+            }
+            Node::ClassConstantAccess(access) => identifiers.extend(class_identifier(access.class)),
+            Node::Binary(binary) if matches!(binary.operator, BinaryOperator::Instanceof(_)) => {
+                identifiers.extend(class_identifier(binary.rhs))
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+/// `hint` as declared, with class names fully qualified, e.g. `?App\Model\User`.
+pub fn render_hint(file: &File<'_>, hint: &Hint<'_>) -> String {
+    match hint {
+        Hint::Identifier(identifier) => file.resolve_class_name(
+            &String::from_utf8_lossy(identifier.value()),
+            identifier.span().start.offset,
+        ),
+        Hint::Parenthesized(parenthesized) => {
+            format!("({})", render_hint(file, parenthesized.hint))
+        }
+        Hint::Nullable(nullable) => format!("?{}", render_hint(file, nullable.hint)),
+        Hint::Union(union) => format!(
+            "{}|{}",
+            render_hint(file, union.left),
+            render_hint(file, union.right)
+        ),
+        Hint::Intersection(intersection) => format!(
+            "{}&{}",
+            render_hint(file, intersection.left),
+            render_hint(file, intersection.right)
+        ),
+        Hint::Null(keyword)
+        | Hint::True(keyword)
+        | Hint::False(keyword)
+        | Hint::Array(keyword)
+        | Hint::Callable(keyword)
+        | Hint::Static(keyword)
+        | Hint::Self_(keyword)
+        | Hint::Parent(keyword) => String::from_utf8_lossy(keyword.value).to_ascii_lowercase(),
+        Hint::Void(identifier)
+        | Hint::Never(identifier)
+        | Hint::Float(identifier)
+        | Hint::Bool(identifier)
+        | Hint::Integer(identifier)
+        | Hint::String(identifier)
+        | Hint::Object(identifier)
+        | Hint::Mixed(identifier)
+        | Hint::Iterable(identifier) => {
+            String::from_utf8_lossy(identifier.value).to_ascii_lowercase()
+        }
+    }
+}
+
+/// The class, `self`, `static` or `parent` a rendered type names on its own,
+/// optionally nullable.
+pub fn single_class(hint: &str) -> Option<&str> {
+    let name = hint.strip_prefix('?').unwrap_or(hint);
+    if name.contains(['|', '&', '(']) {
+        return None;
+    }
+    match name {
+        "self" | "static" | "parent" => Some(name),
+        _ if is_builtin_type(name) => None,
+        _ => Some(name),
+    }
+}
+
+impl fmt::Display for ClassLike {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            SymbolKind::Class => "class",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Function => "function",
+        };
+        write!(
+            f,
+            "{kind} {} (constants: {}, properties: {}, methods: {})",
+            self.name,
+            self.constants.len(),
+            self.properties.len(),
+            self.methods.len()
+        )
+    }
+}
+
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parameters: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|parameter| match &parameter.hint {
+                Some(hint) => format!("{hint} ${}", parameter.name),
+                None => format!("${}", parameter.name),
+            })
+            .collect();
+        write!(f, "{}({})", self.name, parameters.join(", "))?;
+        if let Some(return_type) = &self.return_type {
+            write!(f, ": {return_type}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mago_allocator::prelude::LocalArena;
+
+    use super::*;
+
+    #[test]
+    fn indexes_class_likes_with_resolved_names() {
+        let content = "<?php\nnamespace App\\Model;\n\nuse App\\Contracts\\HasId;\n\nabstract class Base implements HasId {\n    const TABLE = 'base';\n    protected ?Base $parent;\n    abstract public function id(): int;\n}\n\nclass User extends Base {\n    use \\App\\Concerns\\Timestamps;\n    public function rename(string $name, ?Clock $clock = null): static { return new User(); }\n}\n\nenum Status { case Active; case Banned; }\n";
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("Model.php"), content.to_string());
+        let index = ProjectIndex::build([&file]);
+
+        let base = index.get("App\\Model\\Base").unwrap();
+        assert!(base.is_abstract);
+        assert_eq!(base.interfaces, vec!["App\\Contracts\\HasId".to_string()]);
+        assert_eq!(base.constants, vec!["TABLE".to_string()]);
+        assert_eq!(
+            base.properties["parent"].as_deref(),
+            Some("?App\\Model\\Base")
+        );
+
+        let user = index.get("App\\Model\\User").unwrap();
+        assert_eq!(user.parent.as_deref(), Some("App\\Model\\Base"));
+        assert_eq!(user.traits, vec!["App\\Concerns\\Timestamps".to_string()]);
+        assert_eq!(
+            user.methods["rename"].to_string(),
+            "rename(string $name, ?App\\Model\\Clock $clock): static"
+        );
+        assert_eq!(
+            user.references
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+            vec![
+                "App\\Concerns\\Timestamps",
+                "App\\Model\\Base",
+                "App\\Model\\Clock"
+            ]
+        );
+        assert_eq!(
+            index
+                .find_method("App\\Model\\User", "id")
+                .and_then(|m| m.return_type.as_deref()),
+            Some("int")
+        );
+        assert_eq!(
+            index.subclasses("App\\Model\\Base").collect::<Vec<_>>(),
+            vec!["App\\Model\\User"]
+        );
+
+        let status = index.get("App\\Model\\Status").unwrap();
+        assert_eq!(
+            status.constants,
+            vec!["Active".to_string(), "Banned".to_string()]
+        );
+    }
+
+    #[test]
+    fn removing_a_file_forgets_its_class_likes() {
+        let arena = LocalArena::new();
+        let base = File::new(
+            &arena,
+            PathBuf::from("Base.php"),
+            "<?php class Base {}".to_string(),
+        );
+        let child = File::new(
+            &arena,
+            PathBuf::from("Child.php"),
+            "<?php class Child extends Base {}".to_string(),
+        );
+        let mut index = ProjectIndex::build([&base, &child]);
+
+        index.remove_file(Path::new("Child.php"));
+
+        assert!(index.get("Child").is_none());
+        assert!(index.get("Base").is_some());
+        assert_eq!(index.subclasses("Base").count(), 0);
+    }
+
+    #[test]
+    fn class_names_are_case_insensitive() {
+        let content = "<?php\nclass Base { public function id(): int {} }\nclass Child extends base {}\nclass Factory {\n    public function make(): CHILD { return new child(); }\n}\n";
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("Child.php"), content.to_string());
+        let index = ProjectIndex::build([&file]);
+
+        assert_eq!(index.get("BASE").map(|c| c.name.as_str()), Some("Base"));
+        assert_eq!(index.subclasses("Base").collect::<Vec<_>>(), vec!["Child"]);
+        assert!(index.find_method("child", "id").is_some());
+        assert_eq!(
+            index.find_method("Child", "ID").map(|m| m.name.as_str()),
+            Some("id")
+        );
+        assert_eq!(index.get("Factory").unwrap().references.len(), 1);
+        assert_eq!(
+            index.get("arrayobject").map(|c| c.name.as_str()),
+            Some("ArrayObject")
+        );
+    }
+
+    #[test]
+    fn lookups_fall_back_to_php_builtins() {
+        let arena = LocalArena::new();
+        let file = File::new(
+            &arena,
+            PathBuf::from("Clock.php"),
+            "<?php\nnamespace App;\nfunction now(): \\DateTimeImmutable {}\n".to_string(),
+        );
+        let index = ProjectIndex::build([&file]);
+
+        assert_eq!(index.class_likes().count(), 0);
+        assert_eq!(
+            index
+                .get("LengthException")
+                .and_then(|c| c.parent.as_deref()),
+            Some("LogicException")
+        );
+        assert_eq!(
+            index
+                .find_method("DateTimeImmutable", "modify")
+                .and_then(|m| m.return_type.as_deref()),
+            Some("DateTimeImmutable")
+        );
+        assert_eq!(
+            index
+                .find_method("PDO", "prepare")
+                .and_then(|m| m.return_type.as_deref()),
+            Some("PDOStatement")
+        );
+        assert_eq!(
+            index
+                .function("DATE_CREATE")
+                .and_then(|f| f.return_type.as_deref()),
+            Some("DateTime")
+        );
+        assert_eq!(
+            index.function("app\\now").map(ToString::to_string),
+            Some("App\\now(): DateTimeImmutable".to_string())
+        );
+    }
+
+    #[test]
+    fn builtin_stubs_parse_without_errors() {
+        let arena = LocalArena::new();
+        let file = File::new(
+            &arena,
+            PathBuf::from("stubs/php.php"),
+            include_str!("stubs/php.php").to_string(),
+        );
+
+        assert_eq!(file.ast.map(|program| program.errors.len()), Some(0));
+    }
+
+    #[test]
+    fn single_class_ignores_builtins_and_compound_types() {
+        assert_eq!(single_class("?App\\User"), Some("App\\User"));
+        assert_eq!(single_class("static"), Some("static"));
+        assert_eq!(single_class("int"), None);
+        assert_eq!(single_class("App\\A|App\\B"), None);
+    }
+
+    #[test]
+    fn builtins_survive_a_rule_out_of_time() {
+        use crate::config::TimeBudget;
+        use crate::rules::guard::FileRun;
+
+        let budget = TimeBudget {
+            per_rule_ms: 1,
+            per_file_ms: 0,
+        };
+        let index = ProjectIndex::default();
+
+        // The first lookup may build the builtins after the deadline passed.
+        let mut run = FileRun::new(&budget);
+        let found = run.run("E0014", || {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            index.get("LengthException").is_some()
+        });
+        assert!(found);
+
+        // The next file still sees them.
+        let mut run = FileRun::new(&budget);
+        assert!(run.run("E0014", || index.find_method("PDO", "prepare").is_some()));
+    }
+}
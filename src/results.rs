@@ -4,6 +4,7 @@ use std::time::Duration;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize, Serializer};
 
+use crate::baseline::FixedEntry;
 use crate::debug_stats::RuleTimings;
 use crate::file::File;
 
@@ -13,6 +14,9 @@ pub struct EngineerEntry {
     pub total_introduced: u64,
     pub net: i64,
     pub rules: HashMap<String, RuleChange>,
+    /// Commits the changes were attributed to, with `--blame --since --commits`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commits: Vec<CommitChange>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -21,6 +25,49 @@ pub struct RuleChange {
     pub introduced: u64,
 }
 
+/// The violations one commit introduced or fixed.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CommitChange {
+    pub sha: String,
+    /// First line of the commit message.
+    pub message: String,
+    pub fixed: u64,
+    pub introduced: u64,
+    pub violations: Vec<CommitViolation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommitViolation {
+    pub rule: String,
+    pub file: String,
+    pub line: usize,
+    /// `true` when the commit fixed the violation, `false` when it introduced it.
+    pub fixed: bool,
+}
+
+impl EngineerEntry {
+    /// Count `violation` towards `sha`, adding the commit on first use.
+    pub fn add_commit_violation(&mut self, sha: &str, violation: CommitViolation) {
+        let index = match self.commits.iter().position(|c| c.sha == sha) {
+            Some(index) => index,
+            None => {
+                self.commits.push(CommitChange {
+                    sha: sha.to_string(),
+                    ..Default::default()
+                });
+                self.commits.len() - 1
+            }
+        };
+        let commit = &mut self.commits[index];
+        if violation.fixed {
+            commit.fixed += 1;
+        } else {
+            commit.introduced += 1;
+        }
+        commit.violations.push(violation);
+    }
+}
+
 pub type EngineerReport = HashMap<String, EngineerEntry>;
 
 /// A diagnostic message. `id` is a stable slug used as a key (e.g. by the
@@ -59,6 +106,20 @@ impl Message {
     }
 }
 
+/// Where a violation stands relative to a baseline, mirroring SARIF's
+/// `baselineState`. Only set when results are marked against a baseline
+/// instead of filtered by it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BaselineState {
+    /// Not covered by the baseline.
+    New,
+    /// Already accounted for in the baseline (reported as suppressed).
+    Unchanged,
+    /// In the baseline but no longer found in the scanned file.
+    Absent,
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct Violation {
     pub rule: String,
@@ -68,6 +129,33 @@ pub struct Violation {
     pub start_column: usize,
     pub end_line: usize,
     pub end_column: usize,
+    /// Fully qualified name of the enclosing class-like, method or function
+    /// (`App\Foo::bar`), when the violation sits inside one.
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub baseline_state: Option<BaselineState>,
+    /// Silenced by a `@phanalist-ignore` comment.
+    #[serde(default)]
+    pub inline_suppressed: bool,
+}
+
+impl Violation {
+    /// True for parse errors and for rules that crashed or ran out of time,
+    /// which outputs report as errors rather than warnings.
+    pub fn is_error(&self) -> bool {
+        self.rule == crate::rules::e31::CODE || self.message.id.ends_with(":internal-error")
+    }
+
+    /// True when the violation is baselined, no longer present or silenced
+    /// inline, i.e. it should not count towards the run's result.
+    pub fn is_suppressed(&self) -> bool {
+        self.inline_suppressed
+            || matches!(
+                self.baseline_state,
+                Some(BaselineState::Unchanged) | Some(BaselineState::Absent)
+            )
+    }
 }
 
 // Custom serialization: emit the structured `message` and also a flat, rendered
@@ -78,7 +166,7 @@ impl Serialize for Violation {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Violation", 8)?;
+        let mut state = serializer.serialize_struct("Violation", 11)?;
         state.serialize_field("rule", &self.rule)?;
         state.serialize_field("line", &self.line)?;
         state.serialize_field("suggestion", &self.message.render())?;
@@ -87,6 +175,19 @@ impl Serialize for Violation {
         state.serialize_field("start_column", &self.start_column)?;
         state.serialize_field("end_line", &self.end_line)?;
         state.serialize_field("end_column", &self.end_column)?;
+        match &self.symbol {
+            Some(symbol) => state.serialize_field("symbol", symbol)?,
+            None => state.skip_field("symbol")?,
+        }
+        match &self.baseline_state {
+            Some(baseline_state) => state.serialize_field("baseline_state", baseline_state)?,
+            None => state.skip_field("baseline_state")?,
+        }
+        if self.inline_suppressed {
+            state.serialize_field("inline_suppressed", &true)?;
+        } else {
+            state.skip_field("inline_suppressed")?;
+        }
         state.end()
     }
 }
@@ -101,6 +202,9 @@ pub struct Results {
     pub rule_timings: Option<RuleTimings>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub engineer_report: Option<EngineerReport>,
+    /// Baseline entries with violations fixed since the baseline was written.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub baseline_fixed: Vec<FixedEntry>,
 }
 
 impl Results {
@@ -115,6 +219,9 @@ impl Results {
 
         for violation in violations {
             current_file_violations.push(violation.clone());
+            if violation.is_suppressed() {
+                continue;
+            }
 
             let mut rule_count = if let Some(count) = self.codes_count.get(&violation.rule) {
                 count.to_owned()
@@ -129,6 +236,26 @@ impl Results {
         self.files.insert(path, current_file_violations);
     }
 
+    /// Recompute `codes_count` from the violations left in `files`, skipping
+    /// suppressed ones. Needed after filtering violations out.
+    pub fn recount_codes(&mut self) {
+        let mut codes_count: HashMap<String, i64> = HashMap::new();
+        for violations in self.files.values() {
+            for violation in violations.iter().filter(|v| !v.is_suppressed()) {
+                *codes_count.entry(violation.rule.clone()).or_insert(0) += 1;
+            }
+        }
+        self.codes_count = codes_count;
+    }
+
+    /// Drop the violations silenced by `@phanalist-ignore` comments, for
+    /// outputs that cannot report them as suppressed.
+    pub fn remove_inline_suppressed(&mut self) {
+        for violations in self.files.values_mut() {
+            violations.retain(|violation| !violation.inline_suppressed);
+        }
+    }
+
     pub fn has_any_violations(&self) -> bool {
         self.codes_count.values().any(|&c| c > 0)
     }
@@ -150,6 +277,7 @@ mod tests {
             duration: None,
             rule_timings: None,
             engineer_report: None,
+            baseline_fixed: vec![],
         }
     }
     fn get_file<'a>(arena: &'a LocalArena, name: &str) -> File<'a> {
@@ -164,6 +292,9 @@ mod tests {
             start_column: 0,
             end_line: 0,
             end_column: 0,
+            symbol: None,
+            baseline_state: None,
+            inline_suppressed: false,
         }
     }
 
@@ -275,4 +406,29 @@ mod tests {
         let results = get_results();
         assert!(!results.has_any_violations());
     }
+
+    #[test]
+    fn test_add_commit_violation_groups_by_sha() {
+        let violation = |line, fixed| CommitViolation {
+            rule: "E0009".to_string(),
+            file: "src/A.php".to_string(),
+            line,
+            fixed,
+        };
+        let mut entry = EngineerEntry::default();
+        entry.add_commit_violation("aaa", violation(3, false));
+        entry.add_commit_violation("bbb", violation(7, true));
+        entry.add_commit_violation("aaa", violation(9, true));
+
+        assert_eq!(entry.commits.len(), 2);
+        assert_eq!(
+            (entry.commits[0].introduced, entry.commits[0].fixed),
+            (1, 1)
+        );
+        assert_eq!(entry.commits[0].violations.len(), 2);
+        assert_eq!(
+            (entry.commits[1].introduced, entry.commits[1].fixed),
+            (0, 1)
+        );
+    }
 }
@@ -60,14 +60,9 @@ impl crate::rules::Rule for Rule {
         violations
     }
 
-    fn traverse_statements_to_validate<'a>(
-        &'a self,
-        flatten_statements: &mut Vec<&'a Statement<'a>>,
-        statement: &'a Statement<'a>,
-    ) {
-        if let Statement::OpeningTag(_) = statement {
-            flatten_statements.push(statement);
-        }
+    fn validate_nested_statements(&self) -> bool {
+        // Opening tags only appear at the top level.
+        false
     }
 }
 
@@ -8,6 +8,7 @@ use serde_json::Value;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::{class_like, function_likes, guard};
 
 pub(crate) static CODE: &str = "E0010";
 static DESCRIPTION: &str = "Npath complexity";
@@ -51,8 +52,8 @@ impl crate::rules::Rule for Rule {
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        if let Statement::Class(class) = statement {
-            for member in class.members.iter() {
+        if let Some((_, members)) = class_like(statement) {
+            for member in members.iter() {
                 if let ClassLikeMember::Method(method) = member {
                     if let MethodBody::Concrete(block) = &method.body {
                         let npath = calculate_npath(&block.statements);
@@ -69,6 +70,23 @@ impl crate::rules::Rule for Rule {
                 }
             }
         }
+
+        // Arrow functions have no body, only an expression; both metrics count
+        // statements, so an arrow function never rises above the minimum.
+        for function in function_likes(statement) {
+            if let Some(body) = function.body() {
+                let npath = calculate_npath(&body.statements);
+                if npath > self.settings.max_paths {
+                    let message = Message::new(
+                        "E0010:function-too-many-paths",
+                        "The body of {name} function has {npath} paths. Reduce the amount of paths.",
+                    )
+                    .arg("name", function.name())
+                    .arg("npath", npath.to_string());
+                    violations.push(self.new_violation(file, message, function.span()));
+                }
+            }
+        }
         violations
     }
 }
@@ -82,6 +100,7 @@ fn calculate_npath(statements: &Sequence<'_, Statement<'_>>) -> i64 {
 }
 
 fn calculate_statement_npath(statement: &Statement<'_>) -> i64 {
+    guard::check_deadline();
     let mut npath = 0;
     match statement {
         Statement::If(if_stmt) => {
@@ -203,4 +222,29 @@ mod tests {
                 .to_string()
         );
     }
+
+    #[test]
+    fn function_with_many_paths() {
+        let violations = analyze_file_for_rule("e10/procedural.php", CODE);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message.render(),
+            "The body of describe function has 210 paths. Reduce the amount of paths."
+        );
+    }
+
+    #[test]
+    fn conditional_function_and_script_closure() {
+        let violations = analyze_file_for_rule("e10/script.php", CODE);
+
+        let messages: Vec<String> = violations.iter().map(|v| v.message.render()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "The body of describe function has 210 paths. Reduce the amount of paths.",
+                "The body of {closure} function has 210 paths. Reduce the amount of paths.",
+            ]
+        );
+    }
 }
@@ -1,8 +1,9 @@
 use mago_span::HasSpan;
-use mago_syntax::cst::{Call, Expression, Statement, UnaryPrefixOperator};
+use mago_syntax::cst::{Node, Statement, UnaryPrefixOperator};
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::find_nodes;
 
 pub(crate) static CODE: &str = "E0011";
 static DESCRIPTION: &str = "Detect the error suppression symbol: @";
@@ -24,45 +25,23 @@ impl crate::rules::Rule for Rule {
     }
 
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
-        let mut violations = Vec::new();
-        let flatten_statements = self.flatten_statements_to_validate(statement);
-
-        for stmt in flatten_statements {
-            if let Statement::Expression(expr_stmt) = stmt {
-                check_expression(file, self, expr_stmt.expression, &mut violations);
-            }
-            if let Statement::Return(ret) = stmt {
-                if let Some(value) = ret.value {
-                    check_expression(file, self, value, &mut violations);
-                }
+        find_nodes(statement, |node| match node {
+            Node::UnaryPrefix(prefix)
+                if matches!(prefix.operator, UnaryPrefixOperator::ErrorControl(_)) =>
+            {
+                Some(prefix.span())
             }
-        }
-
-        violations
-    }
-}
-
-fn check_expression(
-    file: &File<'_>,
-    rule: &dyn crate::rules::Rule,
-    expr: &Expression<'_>,
-    violations: &mut Vec<Violation>,
-) {
-    match expr {
-        Expression::UnaryPrefix(prefix) => {
-            if let UnaryPrefixOperator::ErrorControl(_) = prefix.operator {
-                let message = Message::new(
-                    "E0011:error-suppression",
-                    "Error supression(@) symbol found. Remove it.",
-                );
-                violations.push(rule.new_violation(file, message, prefix.span()));
-            }
-            check_expression(file, rule, prefix.operand, violations);
-        }
-        Expression::Call(Call::Method(m)) => {
-            check_expression(file, rule, m.object, violations);
-        }
-        _ => {}
+            _ => None,
+        })
+        .into_iter()
+        .map(|span| {
+            let message = Message::new(
+                "E0011:error-suppression",
+                "Error supression(@) symbol found. Remove it.",
+            );
+            self.new_violation(file, message, span)
+        })
+        .collect()
     }
 }
 
@@ -76,6 +55,16 @@ mod tests {
     fn example() {
         let violations = analyze_file_for_rule("e11/detect_@.php", CODE);
 
-        assert!(violations.len().gt(&0));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].symbol, Some("E11::example".to_string()));
+    }
+
+    #[test]
+    fn in_functions_and_closures() {
+        let violations = analyze_file_for_rule("e11/procedural.php", CODE);
+
+        let lines: Vec<usize> = violations.iter().map(|v| v.start_line).collect();
+        assert_eq!(lines, vec![4, 8]);
+        assert_eq!(violations[0].symbol, Some("load".to_string()));
     }
 }
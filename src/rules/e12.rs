@@ -52,22 +52,19 @@ impl RuleTrait for Rule {
     }
 
     fn do_validate(&self, file: &File<'_>) -> bool {
-        if let Some(ns) = &file.namespace {
-            return crate::rules::do_validate_namespace(
-                ns.clone(),
-                &self.settings.include_namespaces,
-                &self.settings.exclude_namespaces,
-            );
-        }
-
-        true
+        file.symbols
+            .iter()
+            .any(|symbol| self.in_scope(symbol.namespace.as_deref()))
     }
 
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
         if let Statement::Class(class) = statement {
-            if self.implements_reset_interface(class) {
+            let namespace = file
+                .symbol_at(class.span().start.offset)
+                .and_then(|symbol| symbol.namespace.as_deref());
+            if !self.in_scope(namespace) || self.implements_reset_interface(class) {
                 return violations;
             }
 
@@ -91,6 +88,17 @@ impl RuleTrait for Rule {
 }
 
 impl Rule {
+    fn in_scope(&self, namespace: Option<&str>) -> bool {
+        match namespace {
+            Some(ns) => crate::rules::do_validate_namespace(
+                ns.to_string(),
+                &self.settings.include_namespaces,
+                &self.settings.exclude_namespaces,
+            ),
+            None => true,
+        }
+    }
+
     fn implements_reset_interface(&self, class: &Class<'_>) -> bool {
         if self.settings.reset_interfaces.is_empty() {
             return false;
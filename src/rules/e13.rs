@@ -5,6 +5,8 @@ use mago_syntax::cst::*;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::class_like;
+use crate::rules::visitor::{self, Scope, Visitor};
 
 pub(crate) static CODE: &str = "E0013";
 static DESCRIPTION: &str = "Private method not being called.";
@@ -27,13 +29,11 @@ impl crate::rules::Rule for Rule {
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        if let Statement::Class(class) = statement {
+        if let Some((_, members)) = class_like(statement) {
             // Collect all private method names and their spans
             let mut private_methods: HashMap<String, Span> = HashMap::new();
-            // Collect all method names that are called anywhere in the class
-            let mut called_methods: HashSet<String> = HashSet::new();
 
-            for member in class.members.iter() {
+            for member in members.iter() {
                 if let ClassLikeMember::Method(method) = member {
                     let method_name = String::from_utf8_lossy(method.name.value).into_owned();
                     let is_private = method
@@ -44,29 +44,14 @@ impl crate::rules::Rule for Rule {
                     if is_private {
                         private_methods.insert(method_name, method.span());
                     }
-
-                    // Scan method body for method calls
-                    if let MethodBody::Concrete(block) = &method.body {
-                        for stmt in block.statements.iter() {
-                            let flat = self.flatten_statements_to_validate(stmt);
-                            for s in flat {
-                                if let Statement::Expression(expr_stmt) = s {
-                                    self.collect_called_methods(
-                                        expr_stmt.expression,
-                                        &mut called_methods,
-                                    );
-                                }
-                                if let Statement::Return(ret) = s {
-                                    if let Some(value) = ret.value {
-                                        self.collect_called_methods(value, &mut called_methods);
-                                    }
-                                }
-                            }
-                        }
-                    }
                 }
             }
 
+            // Collect all method names that are called anywhere in the class
+            let mut called = CalledMethods::default();
+            visitor::walk(Node::Statement(statement), &mut called);
+            let called_methods = called.0;
+
             // Report private methods that are never called
             for (name, span) in &private_methods {
                 if !called_methods.contains(name) {
@@ -84,35 +69,37 @@ impl crate::rules::Rule for Rule {
     }
 }
 
-impl Rule {
-    fn collect_called_methods(&self, expr: &Expression<'_>, called: &mut HashSet<String>) {
-        match expr {
-            Expression::Call(Call::Method(m)) => {
-                // $this->methodName()
-                if let ClassLikeMemberSelector::Identifier(id) = &m.method {
-                    called.insert(String::from_utf8_lossy(id.value).into_owned());
-                }
-                self.collect_called_methods(m.object, called);
-                for arg in m.argument_list.arguments.iter() {
-                    match arg {
-                        Argument::Positional(a) => {
-                            self.collect_called_methods(a.value, called);
-                        }
-                        Argument::Named(a) => {
-                            self.collect_called_methods(a.value, called);
-                        }
-                    }
-                }
-            }
-            Expression::Binary(bin) => {
-                self.collect_called_methods(bin.lhs, called);
-                self.collect_called_methods(bin.rhs, called);
+/// Names of the methods called on `$this`, `self::` or `static::`, closures
+/// and anonymous classes included.
+#[derive(Default)]
+struct CalledMethods(HashSet<String>);
+
+impl<'ast, 'arena> Visitor<'ast, 'arena> for CalledMethods {
+    fn enter(&mut self, node: Node<'ast, 'arena>, _scope: &Scope) -> bool {
+        let selector = match node {
+            Node::MethodCall(call) if is_this(call.object) => &call.method,
+            Node::NullSafeMethodCall(call) if is_this(call.object) => &call.method,
+            Node::StaticMethodCall(call)
+                if matches!(call.class, Expression::Self_(_) | Expression::Static(_)) =>
+            {
+                &call.method
             }
-            _ => {}
+            _ => return true,
+        };
+        if let ClassLikeMemberSelector::Identifier(id) = selector {
+            self.0.insert(String::from_utf8_lossy(id.value).into_owned());
         }
+        true
     }
 }
 
+fn is_this(expression: &Expression<'_>) -> bool {
+    if let Expression::Variable(Variable::Direct(direct)) = expression {
+        return direct.name == b"$this";
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use crate::rules::tests::analyze_file_for_rule;
@@ -125,4 +112,18 @@ mod tests {
         println!("{}", violations.len());
         assert!(violations.len().eq(&3));
     }
+
+    #[test]
+    fn called_from_callbacks() {
+        let violations = analyze_file_for_rule("e13/called_from_callbacks.php", CODE);
+
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn called_on_other_object() {
+        let violations = analyze_file_for_rule("e13/called_on_other_object.php", CODE);
+
+        assert_eq!(violations.len(), 2);
+    }
 }
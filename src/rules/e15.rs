@@ -7,6 +7,8 @@ use serde_json::Value;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::flatten_statements;
+use crate::rules::guard;
 use crate::rules::Rule as RuleTrait;
 
 pub(crate) static CODE: &str = "E0015";
@@ -172,7 +174,7 @@ impl RuleTrait for Rule {
 
                 if let Some(stmts) = node.body_statements() {
                     for stmt in stmts {
-                        for s in self.flatten_statements_to_validate(stmt) {
+                        for s in flatten_statements(stmt) {
                             self.scan_statement(
                                 s,
                                 &property_names,
@@ -245,6 +247,7 @@ impl Rule {
         used_props: &mut HashSet<String>,
         called_methods: &mut HashSet<String>,
     ) {
+        guard::check_deadline();
         match stmt {
             Statement::Expression(s) => {
                 self.scan_expression(s.expression, property_names, used_props, called_methods)
@@ -270,6 +273,7 @@ impl Rule {
         used_props: &mut HashSet<String>,
         called_methods: &mut HashSet<String>,
     ) {
+        guard::check_deadline();
         match expr {
             Expression::Call(call) => match call {
                 Call::Method(m) => {
@@ -384,12 +388,8 @@ impl Rule {
                     }
                 }
             }
-            Expression::Closure(c) => {
-                for stmt in c.body.statements.iter() {
-                    for s in self.flatten_statements_to_validate(stmt) {
-                        self.scan_statement(s, property_names, used_props, called_methods);
-                    }
-                }
+            Expression::Closure(_) => {
+                // Its body statements are among the flattened method statements
             }
             Expression::ArrowFunction(c) => {
                 self.scan_expression(c.expression, property_names, used_props, called_methods);
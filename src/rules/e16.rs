@@ -5,6 +5,7 @@ use serde_json::Value;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::{class_like, function_likes, guard};
 
 pub(crate) static CODE: &str = "E0016";
 static DESCRIPTION: &str = "Cognitive complexity";
@@ -48,8 +49,8 @@ impl crate::rules::Rule for Rule {
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        if let Statement::Class(class) = statement {
-            for member in class.members.iter() {
+        if let Some((_, members)) = class_like(statement) {
+            for member in members.iter() {
                 if let ClassLikeMember::Method(method) = member {
                     if let MethodBody::Concrete(block) = &method.body {
                         let complexity = calculate_cognitive_complexity(&block.statements, 0);
@@ -67,11 +68,32 @@ impl crate::rules::Rule for Rule {
                 }
             }
         }
+
+        for function in function_likes(statement) {
+            let complexity = match (function.body(), function.expression()) {
+                (Some(body), _) => calculate_cognitive_complexity(&body.statements, 0),
+                (None, Some(expression)) => calculate_expression_complexity(expression),
+                (None, None) => 0,
+            };
+
+            if complexity > self.settings.max_complexity {
+                let message = Message::new(
+                    "E0016:function-high-cognitive-complexity",
+                    "The body of {name} function has {complexity} cognitive complexity. Make it easier to understand.",
+                )
+                .arg("name", function.name())
+                .arg("complexity", complexity.to_string());
+                violations.push(self.new_violation(file, message, function.span()));
+            }
+        }
         violations
     }
 }
 
-fn calculate_cognitive_complexity(statements: &Sequence<'_, Statement<'_>>, nesting: i64) -> i64 {
+pub(crate) fn calculate_cognitive_complexity(
+    statements: &Sequence<'_, Statement<'_>>,
+    nesting: i64,
+) -> i64 {
     let mut complexity = 0;
     for statement in statements.iter() {
         complexity += calculate_statement_cognitive_complexity(statement, nesting);
@@ -80,6 +102,7 @@ fn calculate_cognitive_complexity(statements: &Sequence<'_, Statement<'_>>, nest
 }
 
 fn calculate_statement_cognitive_complexity(statement: &Statement<'_>, nesting: i64) -> i64 {
+    guard::check_deadline();
     let mut complexity = 0;
     match statement {
         Statement::If(if_stmt) => {
@@ -213,6 +236,7 @@ fn calculate_statement_cognitive_complexity(statement: &Statement<'_>, nesting:
 }
 
 fn calculate_expression_complexity(expression: &Expression<'_>) -> i64 {
+    guard::check_deadline();
     let mut complexity = 0;
     match expression {
         Expression::Conditional(cond) => {
@@ -300,4 +324,15 @@ mod tests {
         let violations = analyze_file_for_rule("e16/simple.php", CODE);
         assert_eq!(violations.len(), 0);
     }
+
+    #[test]
+    fn complex_closure() {
+        let violations = analyze_file_for_rule("e16/procedural.php", CODE);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message.render(),
+            "The body of {closure} function has 21 cognitive complexity. Make it easier to understand."
+        );
+    }
 }
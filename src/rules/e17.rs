@@ -5,7 +5,7 @@ use mago_syntax::cst::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::file::File;
+use crate::file::{is_builtin_type, File};
 use crate::results::{Message, Violation};
 use crate::rules::Rule as RuleTrait;
 
@@ -71,6 +71,14 @@ impl RuleTrait for Rule {
                 self.scan_class_member(member, &current_class, &mut coupled_types);
             }
 
+            let offset = class.span().start.offset;
+            let own_name = file.resolve_class_name(&current_class, offset);
+            let coupled_types: HashSet<String> = coupled_types
+                .iter()
+                .map(|name| file.resolve_class_name(name, offset))
+                .filter(|name| !name.eq_ignore_ascii_case(&own_name))
+                .collect();
+
             let coupling = coupled_types.len();
             if coupling > self.settings.max_coupling {
                 let mut names = coupled_types.into_iter().collect::<Vec<_>>();
@@ -688,46 +696,17 @@ impl Rule {
         }
     }
 
+    /// Records `name` as written; `validate` resolves the collected names once
+    /// the whole class, which shares one namespace, has been scanned.
     fn add_type_name(&self, name: &str, current_class: &str, coupled_types: &mut HashSet<String>) {
-        let normalized = name.trim_start_matches('\\');
-        let short_name = normalized.rsplit('\\').next().unwrap_or(normalized);
-
-        if normalized.is_empty()
-            || normalized.eq_ignore_ascii_case(current_class)
-            || short_name.eq_ignore_ascii_case(current_class)
-            || matches!(short_name, "self" | "static" | "parent")
-            || is_builtin_type(short_name)
-        {
+        if name.is_empty() || name.eq_ignore_ascii_case(current_class) || is_builtin_type(name) {
             return;
         }
 
-        coupled_types.insert(normalized.to_string());
+        coupled_types.insert(name.to_string());
     }
 }
 
-fn is_builtin_type(name: &str) -> bool {
-    matches!(
-        name.to_ascii_lowercase().as_str(),
-        "array"
-            | "bool"
-            | "boolean"
-            | "callable"
-            | "false"
-            | "float"
-            | "int"
-            | "integer"
-            | "iterable"
-            | "mixed"
-            | "never"
-            | "null"
-            | "object"
-            | "resource"
-            | "string"
-            | "true"
-            | "void"
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use crate::rules::tests::analyze_file_for_rule;
@@ -766,4 +745,15 @@ mod tests {
 
         assert_eq!(violations.len(), 0);
     }
+
+    #[test]
+    fn aliases_and_relative_names_are_counted_once() {
+        let violations = analyze_file_for_rule("e17/aliased_imports.php", CODE);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message.render(),
+            "Class \"InvoiceMailer\" is coupled to 11 external types (App\\Billing\\Gateway, App\\Billing\\Pdf\\Document, App\\Billing\\Queue, App\\Billing\\Renderer, App\\Billing\\Stamp, App\\Billing\\TaxCalculator, App\\Mail\\Mailer, App\\Model\\Customer, App\\Model\\Invoice, DateTimeImmutable, Psr\\Log\\NullLogger). Reduce the number of collaborators or split responsibilities."
+        );
+    }
 }
@@ -62,4 +62,12 @@ mod tests {
 
         assert!(violations.len().eq(&0));
     }
+
+    #[test]
+    fn empty_catch_in_callbacks() {
+        let violations = analyze_file_for_rule("e2/empty_catch_in_callbacks.php", CODE);
+
+        let lines: Vec<usize> = violations.iter().map(|v| v.start_line).collect();
+        assert_eq!(lines, vec![8, 18]);
+    }
 }
@@ -1,6 +1,3 @@
-use std::collections::HashMap;
-use std::sync::Mutex;
-
 use mago_span::HasSpan;
 use mago_syntax::cst::*;
 use serde::{Deserialize, Serialize};
@@ -8,6 +5,7 @@ use serde_json::Value;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::resolve_class_name;
 use crate::rules::Rule as RuleTrait;
 
 pub(crate) static CODE: &str = "E0020";
@@ -24,24 +22,9 @@ impl Default for Settings {
     }
 }
 
-/// Global index: child class name → parent class name.
 #[derive(Default)]
-struct InheritanceIndex {
-    extends: HashMap<String, String>,
-}
-
 pub struct Rule {
     pub settings: Settings,
-    index: Mutex<InheritanceIndex>,
-}
-
-impl Default for Rule {
-    fn default() -> Self {
-        Self {
-            settings: Settings::default(),
-            index: Mutex::new(InheritanceIndex::default()),
-        }
-    }
 }
 
 impl RuleTrait for Rule {
@@ -64,12 +47,8 @@ impl RuleTrait for Rule {
         }
     }
 
-    fn index_file(&self, file: &File<'_>) {
-        if let Some(program) = file.ast {
-            for statement in program.statements.iter() {
-                self.collect_extends(statement);
-            }
-        }
+    fn is_cross_file(&self) -> bool {
+        true
     }
 
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
@@ -77,7 +56,8 @@ impl RuleTrait for Rule {
 
         if let Statement::Class(class) = statement {
             let class_name = String::from_utf8_lossy(class.name.value).into_owned();
-            let depth = self.compute_depth(&class_name);
+            let depth =
+                self.compute_depth(file, &resolve_class_name(file, class.name.value, class.span()));
 
             if depth > self.settings.max_depth {
                 let message = Message::new(
@@ -96,51 +76,18 @@ impl RuleTrait for Rule {
 }
 
 impl Rule {
-    fn collect_extends(&self, statement: &Statement<'_>) {
-        match statement {
-            Statement::Namespace(ns) => {
-                for s in ns.statements().iter() {
-                    self.collect_extends(s);
-                }
-            }
-            Statement::Class(class) => {
-                if let Some(extends) = &class.extends {
-                    for parent in extends.types.iter() {
-                        let child = String::from_utf8_lossy(class.name.value).into_owned();
-                        let parent_str = std::str::from_utf8(parent.value()).unwrap_or_default();
-                        let parent_name = parent_str
-                            .trim_start_matches('\\')
-                            .rsplit('\\')
-                            .next()
-                            .unwrap_or(parent_str)
-                            .to_string();
-                        if let Ok(mut index) = self.index.lock() {
-                            index.extends.insert(child, parent_name);
-                        }
-                    }
-                }
-            }
-            _ => {}
-        }
-    }
-
-    fn compute_depth(&self, class_name: &str) -> usize {
-        let index = match self.index.lock() {
-            Ok(idx) => idx,
-            Err(_) => return 0,
-        };
+    fn compute_depth(&self, file: &File<'_>, class_name: &str) -> usize {
+        let project = file.project();
 
         let mut depth = 0;
-        let mut current = class_name.to_string();
+        let mut current = class_name;
         // Guard against circular inheritance — cap at 100 iterations
         let max_iterations = 100;
-        let mut iterations = 0;
 
-        while let Some(parent) = index.extends.get(&current) {
+        while let Some(parent) = project.get(current).and_then(|c| c.parent.as_deref()) {
             depth += 1;
-            current = parent.clone();
-            iterations += 1;
-            if iterations >= max_iterations {
+            current = parent;
+            if depth >= max_iterations {
                 break;
             }
         }
@@ -177,6 +124,16 @@ mod tests {
         assert_eq!(violations.len(), 0);
     }
 
+    #[test]
+    fn builtin_parents_counted() {
+        let violations = analyze_file_for_rule("e20/builtin_parent.php", CODE);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0]
+            .message
+            .render()
+            .contains("\"OrderNotFound\" has an inheritance depth of 5"));
+    }
+
     #[test]
     fn interface_not_counted() {
         let violations = analyze_file_for_rule("e20/interface_not_counted.php", CODE);
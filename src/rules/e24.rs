@@ -1,10 +1,11 @@
 use mago_span::HasSpan;
-use mago_syntax::cst::{ClassLikeMember, Statement, MethodBody};
+use mago_syntax::cst::{Block, ClassLikeMember, Statement, MethodBody};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::function_likes;
 use crate::rules::Rule as RuleTrait;
 
 pub(crate) static CODE: &str = "E0024";
@@ -36,13 +37,7 @@ impl Rule {
         for member in members.iter() {
             if let ClassLikeMember::Method(method) = member {
                 if let MethodBody::Concrete(block) = &method.body {
-                    let start_line = file.line_number(block.span().start.offset);
-                    let end_line = file.line_number(block.span().end.offset);
-
-                    if end_line <= start_line + 1 {
-                        continue;
-                    }
-                    let loc = end_line - start_line - 1;
+                    let loc = body_loc(file, block);
                     if loc > self.settings.max_loc {
                         let message = Message::new(
                             "E0024:method-too-long",
@@ -59,6 +54,13 @@ impl Rule {
     }
 }
 
+/// Lines between the braces of a method or function body.
+pub(crate) fn body_loc(file: &File<'_>, block: &Block<'_>) -> usize {
+    let start_line = file.line_number(block.span().start.offset);
+    let end_line = file.line_number(block.span().end.offset);
+    end_line.saturating_sub(start_line + 1)
+}
+
 impl crate::rules::Rule for Rule {
     fn get_code(&self) -> String {
         String::from(CODE)
@@ -95,6 +97,27 @@ impl crate::rules::Rule for Rule {
             _ => {}
         }
 
+        for function in function_likes(statement) {
+            let loc = match (function.body(), function.expression()) {
+                (Some(body), _) => body_loc(file, body),
+                (None, Some(expression)) => {
+                    let span = expression.span();
+                    file.line_number(span.end.offset) - file.line_number(span.start.offset) + 1
+                }
+                (None, None) => 0,
+            };
+            if loc > self.settings.max_loc {
+                let message = Message::new(
+                    "E0024:function-too-long",
+                    "Function \"{name}\" has {loc} lines of code (max: {max}). Consider breaking it into smaller functions.",
+                )
+                .arg("name", function.name())
+                .arg("loc", loc.to_string())
+                .arg("max", self.settings.max_loc.to_string());
+                violations.push(self.new_violation(file, message, function.span()));
+            }
+        }
+
         violations
     }
 }
@@ -117,4 +140,15 @@ mod tests {
         let violations = analyze_file_for_rule("e24/short_method.php", CODE);
         assert!(violations.len().eq(&0));
     }
+
+    #[test]
+    fn long_function() {
+        let violations = analyze_file_for_rule("e24/procedural.php", CODE);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message.render(),
+            "Function \"bootstrap\" has 53 lines of code (max: 50). Consider breaking it into smaller functions."
+        );
+    }
 }
@@ -3,6 +3,7 @@ use mago_syntax::cst::{ClassLikeMember, Modifier, Sequence, Statement};
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::class_like;
 
 pub struct Rule {}
 
@@ -25,14 +26,7 @@ impl crate::rules::Rule for Rule {
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        let members = match statement {
-            Statement::Class(class) => Some(&class.members),
-            Statement::Interface(interface) => Some(&interface.members),
-            Statement::Trait(t) => Some(&t.members),
-            _ => None,
-        };
-
-        if let Some(members) = members {
+        if let Some((_, members)) = class_like(statement) {
             for member in members.iter() {
                 if let ClassLikeMember::Method(method) = member {
                     if !self.has_visibility_modifier(&method.modifiers) {
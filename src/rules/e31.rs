@@ -0,0 +1,69 @@
+use mago_span::HasSpan;
+use mago_syntax::cst::Statement;
+
+use crate::file::File;
+use crate::results::{Message, Violation};
+use crate::rules::Rule as RuleTrait;
+
+pub(crate) static CODE: &str = "E0031";
+static DESCRIPTION: &str = "Parse error";
+
+pub struct Rule {}
+
+impl RuleTrait for Rule {
+    fn get_code(&self) -> String {
+        String::from(CODE)
+    }
+
+    fn description(&self) -> String {
+        String::from(DESCRIPTION)
+    }
+
+    /// Broken files may not declare anything the parser could make out.
+    fn do_validate(&self, _file: &File<'_>) -> bool {
+        true
+    }
+
+    fn validate_file(&self, file: &File<'_>) -> Vec<Violation> {
+        let Some(program) = file.ast else {
+            return vec![];
+        };
+
+        program
+            .errors
+            .iter()
+            .map(|error| {
+                let message = Message::new("E0031:parse-error", "Parse error: {error}.")
+                    .arg("error", error.to_string());
+                self.new_violation(file, message, error.span())
+            })
+            .collect()
+    }
+
+    fn validate(&self, _file: &File<'_>, _statement: &Statement<'_>) -> Vec<Violation> {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules::tests::analyze_file_for_rule;
+
+    use super::*;
+
+    #[test]
+    fn missing_semicolon() {
+        let violations = analyze_file_for_rule("e31/missing_semicolon.php", CODE);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].start_line, 8);
+        assert!(violations[0].message.render().starts_with("Parse error: "));
+    }
+
+    #[test]
+    fn valid() {
+        let violations = analyze_file_for_rule("e31/valid.php", CODE);
+
+        assert!(violations.is_empty());
+    }
+}
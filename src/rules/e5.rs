@@ -3,6 +3,7 @@ use mago_syntax::cst::Statement;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::class_like;
 
 pub(crate) static CODE: &str = "E0005";
 static DESCRIPTION: &str = "Capitalized class name";
@@ -25,8 +26,8 @@ impl crate::rules::Rule for Rule {
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        if let Statement::Class(class) = statement {
-            let name = class.name.value;
+        if let Some((identifier, _)) = class_like(statement) {
+            let name = identifier.value;
             let name_str = std::str::from_utf8(name).unwrap_or_default();
             if let Some(first) = name_str.chars().next() {
                 if !first.is_uppercase() {
@@ -35,7 +36,7 @@ impl crate::rules::Rule for Rule {
                         "The class name {name} is not capitalized. The first letter of the name of the class should be in uppercase.",
                     )
                     .arg("name", name_str.to_string());
-                    violations.push(self.new_violation(file, message, class.name.span()))
+                    violations.push(self.new_violation(file, message, identifier.span()))
                 }
             }
         };
@@ -61,6 +62,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn every_class_like_in_the_file() {
+        let violations = analyze_file_for_rule("e5/several_class_likes.php", CODE);
+
+        let names: Vec<_> = violations.iter().map(|v| v.symbol.clone()).collect();
+        assert_eq!(
+            names,
+            vec![
+                Some("Test\\e5\\secondClass".to_string()),
+                Some("Test\\e5\\lowercaseInterface".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn capitalized_classname() {
         let violations = analyze_file_for_rule("e5/capitalized_classname.php", CODE);
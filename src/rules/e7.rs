@@ -5,6 +5,7 @@ use serde_json::Value;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::{class_like, function_likes};
 
 pub(crate) static CODE: &str = "E0007";
 static DESCRIPTION: &str = "Method parameters count";
@@ -52,8 +53,8 @@ impl crate::rules::Rule for Rule {
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        if let Statement::Class(class) = statement {
-            for member in class.members.iter() {
+        if let Some((_, members)) = class_like(statement) {
+            for member in members.iter() {
                 if let ClassLikeMember::Method(method) = member {
                     let name = method.name.value;
                     let parameters_count = method.parameter_list.parameters.len();
@@ -82,6 +83,18 @@ impl crate::rules::Rule for Rule {
             }
         }
 
+        for function in function_likes(statement) {
+            if function.parameter_list().parameters.len() > self.settings.max_parameters as usize {
+                let message = Message::new(
+                    "E0007:function-too-many-parameters",
+                    "Function {name} has too many parameters. More than {max} parameters is considered a too much.",
+                )
+                .arg("name", function.name())
+                .arg("max", self.settings.max_parameters.to_string());
+                violations.push(self.new_violation(file, message, function.span()));
+            }
+        }
+
         violations
     }
 }
@@ -116,10 +129,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn interface_and_trait_max_params() {
+        let violations = analyze_file_for_rule("e7/interface_and_trait_max_params.php", CODE);
+
+        let symbols: Vec<_> = violations.iter().map(|v| v.symbol.clone()).collect();
+        assert_eq!(
+            symbols,
+            vec![
+                Some("Test\\e7\\Importer::import".to_string()),
+                Some("Test\\e7\\Exporter::export".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn valid_amount_of_params() {
         let violations = analyze_file_for_rule("e7/valid_amount_of_params.php", CODE);
 
         assert!(violations.len().eq(&0));
     }
+
+    #[test]
+    fn functions_closures_and_arrow_functions() {
+        let violations = analyze_file_for_rule("e7/procedural.php", CODE);
+
+        let messages: Vec<String> = violations.iter().map(|v| v.message.render()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Function import_rows has too many parameters. More than 8 parameters is considered a too much.",
+                "Function {closure} has too many parameters. More than 8 parameters is considered a too much.",
+                "Function {arrow function} has too many parameters. More than 8 parameters is considered a too much.",
+            ]
+        );
+        assert_eq!(violations[0].symbol, Some("import_rows".to_string()));
+    }
 }
@@ -3,6 +3,7 @@ use mago_syntax::cst::{ClassLikeMember, MethodBody, Statement};
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::class_like;
 
 pub(crate) static CODE: &str = "E0008";
 static DESCRIPTION: &str = "Return type signature";
@@ -25,8 +26,8 @@ impl crate::rules::Rule for Rule {
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        if let Statement::Class(class) = statement {
-            for member in class.members.iter() {
+        if let Some((_, members)) = class_like(statement) {
+            for member in members.iter() {
                 if let ClassLikeMember::Method(method) = member {
                     // Only check concrete methods (not abstract)
                     if let MethodBody::Concrete(block) = &method.body {
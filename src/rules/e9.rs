@@ -8,6 +8,7 @@ use serde_json::Value;
 
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::{class_like, function_likes, guard};
 
 pub(crate) static CODE: &str = "E0009";
 static DESCRIPTION: &str = "Cyclomatic complexity";
@@ -51,8 +52,8 @@ impl crate::rules::Rule for Rule {
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        if let Statement::Class(class) = statement {
-            for member in class.members.iter() {
+        if let Some((_, members)) = class_like(statement) {
+            for member in members.iter() {
                 if let ClassLikeMember::Method(method) = member {
                     if let MethodBody::Concrete(block) = &method.body {
                         // Base complexity is 1 for the method itself
@@ -71,6 +72,24 @@ impl crate::rules::Rule for Rule {
                 }
             }
         }
+
+        // Arrow functions have no body, only an expression; both metrics count
+        // statements, so an arrow function never rises above the minimum.
+        for function in function_likes(statement) {
+            if let Some(body) = function.body() {
+                let complexity = 1 + calculate_complexity(&body.statements);
+
+                if complexity > self.settings.max_complexity {
+                    let message = Message::new(
+                        "E0009:function-high-cyclomatic-complexity",
+                        "The body of {name} function has {complexity} complexity. Make it easier to understand.",
+                    )
+                    .arg("name", function.name())
+                    .arg("complexity", complexity.to_string());
+                    violations.push(self.new_violation(file, message, function.span()));
+                }
+            }
+        }
         violations
     }
 }
@@ -84,6 +103,7 @@ pub(crate) fn calculate_complexity(statements: &Sequence<'_, Statement<'_>>) ->
 }
 
 fn calculate_statement_complexity(statement: &Statement<'_>) -> i64 {
+    guard::check_deadline();
     let mut complexity = 0;
     match statement {
         Statement::If(if_stmt) => {
@@ -214,4 +234,29 @@ mod tests {
 
         assert!(violations.len().eq(&0));
     }
+
+    #[test]
+    fn complex_function() {
+        let violations = analyze_file_for_rule("e9/procedural.php", CODE);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message.render(),
+            "The body of route function has 12 complexity. Make it easier to understand."
+        );
+    }
+
+    #[test]
+    fn conditional_function_and_script_closure() {
+        let violations = analyze_file_for_rule("e9/script.php", CODE);
+
+        let messages: Vec<String> = violations.iter().map(|v| v.message.render()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "The body of route function has 11 complexity. Make it easier to understand.",
+                "The body of {closure} function has 11 complexity. Make it easier to understand.",
+            ]
+        );
+    }
 }
@@ -0,0 +1,76 @@
+<?php
+
+function describe($flags, $a, $b) {
+    $out = [];
+    if ($flags === 0) { if ($a) { if ($b) { $out[] = 0; } } }
+    if ($flags === 1) { if ($a) { if ($b) { $out[] = 1; } } }
+    if ($flags === 2) { if ($a) { if ($b) { $out[] = 2; } } }
+    if ($flags === 3) { if ($a) { if ($b) { $out[] = 3; } } }
+    if ($flags === 4) { if ($a) { if ($b) { $out[] = 4; } } }
+    if ($flags === 5) { if ($a) { if ($b) { $out[] = 5; } } }
+    if ($flags === 6) { if ($a) { if ($b) { $out[] = 6; } } }
+    if ($flags === 7) { if ($a) { if ($b) { $out[] = 7; } } }
+    if ($flags === 8) { if ($a) { if ($b) { $out[] = 8; } } }
+    if ($flags === 9) { if ($a) { if ($b) { $out[] = 9; } } }
+    if ($flags === 10) { if ($a) { if ($b) { $out[] = 10; } } }
+    if ($flags === 11) { if ($a) { if ($b) { $out[] = 11; } } }
+    if ($flags === 12) { if ($a) { if ($b) { $out[] = 12; } } }
+    if ($flags === 13) { if ($a) { if ($b) { $out[] = 13; } } }
+    if ($flags === 14) { if ($a) { if ($b) { $out[] = 14; } } }
+    if ($flags === 15) { if ($a) { if ($b) { $out[] = 15; } } }
+    if ($flags === 16) { if ($a) { if ($b) { $out[] = 16; } } }
+    if ($flags === 17) { if ($a) { if ($b) { $out[] = 17; } } }
+    if ($flags === 18) { if ($a) { if ($b) { $out[] = 18; } } }
+    if ($flags === 19) { if ($a) { if ($b) { $out[] = 19; } } }
+    if ($flags === 20) { if ($a) { if ($b) { $out[] = 20; } } }
+    if ($flags === 21) { if ($a) { if ($b) { $out[] = 21; } } }
+    if ($flags === 22) { if ($a) { if ($b) { $out[] = 22; } } }
+    if ($flags === 23) { if ($a) { if ($b) { $out[] = 23; } } }
+    if ($flags === 24) { if ($a) { if ($b) { $out[] = 24; } } }
+    if ($flags === 25) { if ($a) { if ($b) { $out[] = 25; } } }
+    if ($flags === 26) { if ($a) { if ($b) { $out[] = 26; } } }
+    if ($flags === 27) { if ($a) { if ($b) { $out[] = 27; } } }
+    if ($flags === 28) { if ($a) { if ($b) { $out[] = 28; } } }
+    if ($flags === 29) { if ($a) { if ($b) { $out[] = 29; } } }
+    if ($flags === 30) { if ($a) { if ($b) { $out[] = 30; } } }
+    if ($flags === 31) { if ($a) { if ($b) { $out[] = 31; } } }
+    if ($flags === 32) { if ($a) { if ($b) { $out[] = 32; } } }
+    if ($flags === 33) { if ($a) { if ($b) { $out[] = 33; } } }
+    if ($flags === 34) { if ($a) { if ($b) { $out[] = 34; } } }
+    if ($flags === 35) { if ($a) { if ($b) { $out[] = 35; } } }
+    if ($flags === 36) { if ($a) { if ($b) { $out[] = 36; } } }
+    if ($flags === 37) { if ($a) { if ($b) { $out[] = 37; } } }
+    if ($flags === 38) { if ($a) { if ($b) { $out[] = 38; } } }
+    if ($flags === 39) { if ($a) { if ($b) { $out[] = 39; } } }
+    if ($flags === 40) { if ($a) { if ($b) { $out[] = 40; } } }
+    if ($flags === 41) { if ($a) { if ($b) { $out[] = 41; } } }
+    if ($flags === 42) { if ($a) { if ($b) { $out[] = 42; } } }
+    if ($flags === 43) { if ($a) { if ($b) { $out[] = 43; } } }
+    if ($flags === 44) { if ($a) { if ($b) { $out[] = 44; } } }
+    if ($flags === 45) { if ($a) { if ($b) { $out[] = 45; } } }
+    if ($flags === 46) { if ($a) { if ($b) { $out[] = 46; } } }
+    if ($flags === 47) { if ($a) { if ($b) { $out[] = 47; } } }
+    if ($flags === 48) { if ($a) { if ($b) { $out[] = 48; } } }
+    if ($flags === 49) { if ($a) { if ($b) { $out[] = 49; } } }
+    if ($flags === 50) { if ($a) { if ($b) { $out[] = 50; } } }
+    if ($flags === 51) { if ($a) { if ($b) { $out[] = 51; } } }
+    if ($flags === 52) { if ($a) { if ($b) { $out[] = 52; } } }
+    if ($flags === 53) { if ($a) { if ($b) { $out[] = 53; } } }
+    if ($flags === 54) { if ($a) { if ($b) { $out[] = 54; } } }
+    if ($flags === 55) { if ($a) { if ($b) { $out[] = 55; } } }
+    if ($flags === 56) { if ($a) { if ($b) { $out[] = 56; } } }
+    if ($flags === 57) { if ($a) { if ($b) { $out[] = 57; } } }
+    if ($flags === 58) { if ($a) { if ($b) { $out[] = 58; } } }
+    if ($flags === 59) { if ($a) { if ($b) { $out[] = 59; } } }
+    if ($flags === 60) { if ($a) { if ($b) { $out[] = 60; } } }
+    if ($flags === 61) { if ($a) { if ($b) { $out[] = 61; } } }
+    if ($flags === 62) { if ($a) { if ($b) { $out[] = 62; } } }
+    if ($flags === 63) { if ($a) { if ($b) { $out[] = 63; } } }
+    if ($flags === 64) { if ($a) { if ($b) { $out[] = 64; } } }
+    if ($flags === 65) { if ($a) { if ($b) { $out[] = 65; } } }
+    if ($flags === 66) { if ($a) { if ($b) { $out[] = 66; } } }
+    if ($flags === 67) { if ($a) { if ($b) { $out[] = 67; } } }
+    if ($flags === 68) { if ($a) { if ($b) { $out[] = 68; } } }
+    if ($flags === 69) { if ($a) { if ($b) { $out[] = 69; } } }
+    return $out;
+}
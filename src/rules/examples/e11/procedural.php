@@ -0,0 +1,10 @@
+<?php
+
+function load($path) {
+    return @file_get_contents($path);
+}
+
+$quiet = function ($path) {
+    $handle = @fopen($path, 'r');
+    return $handle;
+};
@@ -0,0 +1,22 @@
+<?php
+
+class Report {
+  public function render(array $rows): array {
+    return array_map(fn ($row) => $this->format($row), $rows);
+  }
+
+  public function totals(array $rows): array {
+    usort($rows, function ($a, $b) {
+      return self::compare($a, $b);
+    });
+    return $rows;
+  }
+
+  private function format($row) {
+    return $row;
+  }
+
+  private static function compare($a, $b): int {
+    return $a <=> $b;
+  }
+}
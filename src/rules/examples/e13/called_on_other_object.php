@@ -0,0 +1,16 @@
+<?php
+
+class Newsletter {
+  public function __construct(private Mailer $mailer) {}
+
+  public function publish(string $issue): void {
+    $this->mailer->send($issue);
+    Archive::store($issue);
+  }
+
+  private function send(string $issue): void {
+  }
+
+  private static function store(string $issue): void {
+  }
+}
@@ -0,0 +1,15 @@
+<?php
+
+namespace Test\e14;
+
+use DateTimeImmutable;
+
+class Schedule {
+    public function nextMonday(DateTimeImmutable $from): string {
+        return $from->modify('next monday')->setTime(9, 0)->format('Y-m-d H:i');
+    }
+
+    public function endOfDay(\DateTime $day): \DateTime {
+        return $day->setTime(23, 59)->setTimezone(new \DateTimeZone('UTC'));
+    }
+}
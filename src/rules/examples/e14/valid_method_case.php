@@ -0,0 +1,21 @@
+<?php
+
+class Options {
+    public function getFoo(): Options {
+        return $this;
+    }
+
+    public function setBar(string $bar): Options {
+        return $this;
+    }
+
+    public function useOptions(Options $x) {
+        $this->GetFoo()->SETBAR('b')->getfoo();
+    }
+}
+
+class OptionsUser {
+    public function configure(Options $x) {
+        $x->GetFoo()->SetBar('a')->getFOO();
+    }
+}
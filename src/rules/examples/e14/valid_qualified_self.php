@@ -0,0 +1,23 @@
+<?php
+
+namespace App\Query;
+
+use App\Query\Builder as QueryBuilder;
+
+class Builder
+{
+    public function where(string $column): QueryBuilder
+    {
+        return $this;
+    }
+
+    public function orderBy(string $column): \App\Query\Builder
+    {
+        return $this;
+    }
+
+    public function build(): string
+    {
+        return $this->where('id')->orderBy('id')->where('name')->build();
+    }
+}
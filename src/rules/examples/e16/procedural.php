@@ -0,0 +1,18 @@
+<?php
+
+add_action('init', function ($v) {
+    if ($v > 0) {
+        if ($v > 1) {
+            if ($v > 2) {
+                if ($v > 3) {
+                    if ($v > 4) {
+                        if ($v > 5) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    return false;
+});
@@ -0,0 +1,30 @@
+<?php
+
+namespace App\Billing;
+
+use App\Model\{Invoice, Customer as Client};
+use App\Model\Invoice as Bill;
+use App\Mail;
+
+class InvoiceMailer
+{
+    public function __construct(
+        private Mail\Mailer $mailer,
+        private \App\Mail\Mailer $fallback,
+        private Gateway $gateway,
+        private namespace\Gateway $backup,
+    ) {}
+
+    public function send(Invoice $invoice, Bill $copy, \App\Model\Invoice $original): Client
+    {
+        $logger = new \Psr\Log\NullLogger();
+        $renderer = new Renderer();
+        $queue = new Queue();
+        $sentAt = new \DateTimeImmutable();
+        $pdf = new Pdf\Document();
+        $tax = TaxCalculator::for($invoice);
+        $stamp = new Stamp();
+
+        return $invoice->customer();
+    }
+}
@@ -0,0 +1,28 @@
+<?php
+
+class Importer {
+  public function import(array $rows): array {
+    return array_map(function ($row) {
+      try {
+        return $this->parse($row);
+      } catch (\Exception $e) {
+      }
+    }, $rows);
+  }
+
+  public function handler(string $format): callable {
+    return match ($format) {
+      'csv' => function ($row) {
+        try {
+          return str_getcsv($row);
+        } catch (\ValueError $e) {
+        }
+      },
+      default => fn ($row) => $row,
+    };
+  }
+
+  private function parse($row) {
+    return $row;
+  }
+}
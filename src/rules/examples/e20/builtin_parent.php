@@ -0,0 +1,6 @@
+<?php
+
+namespace Test\e20;
+
+class OrderException extends \BadMethodCallException {}  // depth = 4 through the built-in exceptions
+class OrderNotFound extends OrderException {}             // depth = 5 → violation
@@ -0,0 +1,30 @@
+<?php
+
+namespace Test\e21\Legacy {
+    class Model {}
+}
+
+namespace Test\e21\Domain {
+    use Test\e21\Legacy\Model as LegacyModel;
+
+    class Model {}
+
+    class Entity1 extends Model {}
+    class Entity2 extends Model {}
+    class Entity3 extends Model {}
+    class Entity4 extends Model {}
+    class Entity5 extends Model {}
+    class Entity6 extends Model {}
+    class Entity7 extends Model {}
+    class Entity8 extends Model {}
+    class Entity9 extends Model {}
+    class Entity10 extends Model {}
+    class Entity11 extends Model {}
+    class Entity12 extends Model {}
+    class Entity13 extends Model {}
+    class Entity14 extends Model {}
+    class Entity15 extends Model {}
+    class Entity16 extends Model {}
+
+    class Archive extends LegacyModel {}
+}
@@ -0,0 +1,10 @@
+<?php
+
+class Invoice
+{
+    public function total(): int
+    {
+        $total = 0
+        return $total;
+    }
+}
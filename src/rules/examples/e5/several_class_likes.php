@@ -0,0 +1,12 @@
+<?php
+
+namespace Test\e5;
+
+class Capitalized {
+}
+
+class secondClass {
+}
+
+interface lowercaseInterface {
+}
@@ -0,0 +1,12 @@
+<?php
+
+namespace Test\e7;
+
+interface Importer {
+    public function import($a, $b, $c, $d, $e, $f, $g, $h, $i);
+}
+
+trait Exporter {
+    public function export($a, $b, $c, $d, $e, $f, $g, $h, $i) {
+    }
+}
@@ -0,0 +1,9 @@
+<?php
+
+function import_rows($a, $b, $c, $d, $e, $f, $g, $h, $i) {
+}
+
+$export = function ($a, $b, $c, $d, $e, $f, $g, $h, $i) {
+};
+
+$format = fn ($a, $b, $c, $d, $e, $f, $g, $h, $i) => $a;
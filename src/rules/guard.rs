@@ -0,0 +1,281 @@
+//! Runs rules on a file so that a panicking or runaway rule is stopped and
+//! reported instead of taking the whole scan down with it.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use crate::config::TimeBudget;
+use crate::results::Message;
+
+thread_local! {
+    /// When the rule running on this thread is out of time.
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    /// Whether a rule is running on this thread, its panics to be reported
+    /// as violations rather than printed.
+    static RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+/// Wrap the panic hook so that it stays silent on threads running a rule. The
+/// hook is process-wide, so it is installed once and other panics still print.
+fn install_quiet_hook() {
+    QUIET_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !RUNNING.get() {
+                hook(info);
+            }
+        }));
+    });
+}
+
+/// Unwinding payload of a rule stopped by `check_deadline`.
+struct OutOfTime;
+
+/// Stop the running rule when it is past its time budget. Called from long
+/// walks such as `visitor::walk` and from the recursive helpers of rules, as a
+/// rule cannot be interrupted otherwise.
+pub(crate) fn check_deadline() {
+    if DEADLINE
+        .get()
+        .is_some_and(|deadline| Instant::now() > deadline)
+    {
+        // Unlike `panic!`, skips the panic hook: this is not a bug to print.
+        panic::resume_unwind(Box::new(OutOfTime));
+    }
+}
+
+/// Run `f` with no deadline, for shared work that must not be cut short by
+/// the rule that happens to trigger it, such as building a lazy static.
+pub(crate) fn without_deadline<T>(f: impl FnOnce() -> T) -> T {
+    let deadline = DEADLINE.take();
+    let result = f();
+    DEADLINE.set(deadline);
+    result
+}
+
+/// Why a rule was stopped on a file.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Failure {
+    Panicked(String),
+    /// Ran past the budget, in milliseconds, of the rule or of the file.
+    TimedOut(u64),
+}
+
+impl Failure {
+    pub(crate) fn message(&self, code: &str, path: &Path) -> Message {
+        let message = match self {
+            Failure::Panicked(reason) => Message::new(
+                format!("{code}:internal-error"),
+                "Rule {rule} crashed on {file} and was skipped: {reason}.",
+            )
+            .arg("reason", reason),
+            Failure::TimedOut(budget) => Message::new(
+                format!("{code}:internal-error"),
+                "Rule {rule} was stopped on {file} after exceeding the time budget of {budget} ms.",
+            )
+            .arg("budget", budget.to_string()),
+        };
+        message
+            .arg("rule", code)
+            .arg("file", path.display().to_string())
+    }
+}
+
+/// The rules run on one file: their time spent and the ones that failed.
+pub(crate) struct FileRun {
+    per_rule: Option<Duration>,
+    per_file: Option<Duration>,
+    deadline: Option<Instant>,
+    spent: HashMap<String, Duration>,
+    failures: Vec<(String, Failure)>,
+}
+
+impl FileRun {
+    pub(crate) fn new(budget: &TimeBudget) -> Self {
+        let limit = |ms| (ms > 0).then(|| Duration::from_millis(ms));
+        let per_file = limit(budget.per_file_ms);
+        Self {
+            per_rule: limit(budget.per_rule_ms),
+            per_file,
+            deadline: per_file.map(|limit| Instant::now() + limit),
+            spent: HashMap::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    /// Run `f` on behalf of the rule `code`. Once the rule panicked or ran
+    /// out of time on this file, and once the file is out of time, `f` is
+    /// skipped and the default value returned.
+    pub(crate) fn run<T: Default>(&mut self, code: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        if self.has_failed(code) || self.deadline.is_some_and(|deadline| start > deadline) {
+            return T::default();
+        }
+
+        let spent = self.spent.get(code).copied().unwrap_or_default();
+        let rule_deadline = self
+            .per_rule
+            .map(|limit| start + limit.saturating_sub(spent));
+        let (deadline, budget) = match (rule_deadline, self.deadline) {
+            (Some(rule), Some(file)) if file < rule => (Some(file), self.per_file),
+            (Some(rule), _) => (Some(rule), self.per_rule),
+            (None, file) => (file, self.per_file),
+        };
+
+        install_quiet_hook();
+        DEADLINE.set(deadline);
+        RUNNING.set(true);
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        RUNNING.set(false);
+        DEADLINE.set(None);
+        *self.spent.entry(code.to_string()).or_default() += start.elapsed();
+
+        let timed_out = || Failure::TimedOut(budget.unwrap_or_default().as_millis() as u64);
+        match result {
+            Ok(value) => {
+                // Finished, but late: keep the result and run the rule no further.
+                if deadline.is_some_and(|deadline| Instant::now() > deadline) {
+                    self.failures.push((code.to_string(), timed_out()));
+                }
+                value
+            }
+            Err(payload) if payload.is::<OutOfTime>() => {
+                self.failures.push((code.to_string(), timed_out()));
+                T::default()
+            }
+            Err(payload) => {
+                self.failures
+                    .push((code.to_string(), Failure::Panicked(panic_reason(&*payload))));
+                T::default()
+            }
+        }
+    }
+
+    fn has_failed(&self, code: &str) -> bool {
+        self.failures.iter().any(|(failed, _)| failed == code)
+    }
+
+    /// Rules stopped on the file, in the order they failed.
+    pub(crate) fn failures(self) -> Vec<(String, Failure)> {
+        self.failures
+    }
+}
+
+fn panic_reason(payload: &(dyn Any + Send)) -> String {
+    if let Some(reason) = payload.downcast_ref::<&str>() {
+        reason.to_string()
+    } else if let Some(reason) = payload.downcast_ref::<String>() {
+        reason.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(per_rule_ms: u64, per_file_ms: u64) -> TimeBudget {
+        TimeBudget {
+            per_rule_ms,
+            per_file_ms,
+        }
+    }
+
+    #[test]
+    fn panic_skips_the_rule_for_the_rest_of_the_file() {
+        let mut run = FileRun::new(&budget(0, 0));
+
+        let first: Vec<u8> = run.run("E0001", || panic!("index out of bounds"));
+        let second = run.run("E0001", || vec![1]);
+        let other = run.run("E0002", || vec![2]);
+
+        assert!(first.is_empty());
+        assert!(second.is_empty());
+        assert_eq!(other, vec![2]);
+        assert_eq!(
+            run.failures(),
+            vec![(
+                "E0001".to_string(),
+                Failure::Panicked("index out of bounds".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn runaway_rule_is_stopped_at_its_deadline() {
+        let mut run = FileRun::new(&budget(20, 0));
+
+        let found: Vec<u8> = run.run("E0014", || loop {
+            check_deadline();
+        });
+
+        assert!(found.is_empty());
+        assert_eq!(
+            run.failures(),
+            vec![("E0014".to_string(), Failure::TimedOut(20))]
+        );
+    }
+
+    #[test]
+    fn late_result_is_kept_but_rule_not_run_again() {
+        let mut run = FileRun::new(&budget(1, 0));
+
+        let late = run.run("E0009", || {
+            std::thread::sleep(Duration::from_millis(5));
+            vec![1]
+        });
+        let skipped = run.run("E0009", || vec![2]);
+
+        assert_eq!(late, vec![1]);
+        assert!(skipped.is_empty());
+        assert_eq!(run.failures().len(), 1);
+    }
+
+    #[test]
+    fn file_budget_is_shared_by_all_rules() {
+        let mut run = FileRun::new(&budget(0, 1));
+
+        run.run("E0009", || std::thread::sleep(Duration::from_millis(5)));
+        let skipped = run.run("E0010", || vec![1]);
+
+        assert!(skipped.is_empty());
+        assert_eq!(
+            run.failures(),
+            vec![("E0009".to_string(), Failure::TimedOut(1))]
+        );
+    }
+
+    #[test]
+    fn deadline_is_lifted_for_shared_work() {
+        let mut run = FileRun::new(&budget(1, 0));
+
+        let found = run.run("E0014", || {
+            std::thread::sleep(Duration::from_millis(5));
+            without_deadline(|| {
+                check_deadline();
+                vec![1]
+            })
+        });
+
+        assert_eq!(found, vec![1]);
+    }
+
+    #[test]
+    fn message_names_rule_and_file() {
+        let message = Failure::TimedOut(500).message("E0014", Path::new("src/Order.php"));
+
+        assert_eq!(message.id, "E0014:internal-error");
+        assert_eq!(
+            message.render(),
+            "Rule E0014 was stopped on src/Order.php after exceeding the time budget of 500 ms."
+        );
+    }
+}
@@ -4,13 +4,14 @@ use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use mago_span::Span;
+use mago_span::{HasSpan, Span};
 use mago_syntax::cst::*;
 use serde_json::Value;
 
 use crate::config::Config;
 use crate::file::File;
 use crate::results::{Message, Violation};
+use crate::rules::visitor::{Scope, Visitor};
 pub mod e0;
 pub mod e1;
 pub mod e10;
@@ -42,11 +43,16 @@ pub mod e27;
 pub mod e28;
 pub mod e29;
 pub mod e30;
+pub mod e31;
+pub mod guard;
+pub mod visitor;
 
 pub trait Rule: Sync {
-    /// Optional hook for cross-file type resolution or indexing.
-    /// Called once for every file before main validation pass.
-    fn index_file(&self, _file: &File<'_>) {}
+    /// True when the rule's violations depend on other files (it reads
+    /// `File::project`), so results of an unchanged file cannot be reused.
+    fn is_cross_file(&self) -> bool {
+        false
+    }
 
     /// Would be a good idea to have default implementation which extracts the code from struct name
     /// Haven't found a way to implement it
@@ -106,8 +112,15 @@ pub trait Rule: Sync {
         );
     }
 
+    /// Whether to run the rule on the file; by default when it declares at
+    /// least one class-like or function.
     fn do_validate(&self, file: &File<'_>) -> bool {
-        file.get_fully_qualified_name().is_some()
+        !file.symbols.is_empty()
+    }
+
+    /// Violations concerning the file as a whole, checked once before its statements.
+    fn validate_file(&self, _file: &File<'_>) -> Vec<Violation> {
+        vec![]
     }
 
     fn validate(&self, file: &File<'_>, statement: &Statement<'_>) -> Vec<Violation>;
@@ -132,160 +145,38 @@ pub trait Rule: Sync {
             start_column,
             end_line,
             end_column,
+            symbol: file.enclosing_symbol(span.start.offset),
+            baseline_state: None,
+            inline_suppressed: false,
         }
     }
 
-    fn flatten_statements_to_validate<'a>(
-        &'a self,
-        statement: &'a Statement<'a>,
-    ) -> Vec<&'a Statement<'a>> {
-        let mut flatten_statements: Vec<&Statement<'a>> = Vec::new();
-        self.traverse_statements_to_validate(&mut flatten_statements, statement);
-        flatten_statements
+    /// Whether `validate` also gets the statements nested in each top-level
+    /// one. Rules walking their statements themselves only want the top level.
+    fn validate_nested_statements(&self) -> bool {
+        true
     }
+}
 
-    fn traverse_statements_to_validate<'a>(
-        &'a self,
-        flatten_statements: &mut Vec<&'a Statement<'a>>,
-        statement: &'a Statement<'a>,
-    ) {
-        flatten_statements.push(statement);
-
-        match statement {
-            Statement::Block(block) => {
-                for s in block.statements.iter() {
-                    self.traverse_statements_to_validate(flatten_statements, s);
-                }
-            }
-            Statement::If(if_stmt) => match &if_stmt.body {
-                IfBody::Statement(body) => {
-                    self.traverse_statements_to_validate(flatten_statements, body.statement);
-                    for clauses in body.else_if_clauses.iter() {
-                        self.traverse_statements_to_validate(flatten_statements, clauses.statement);
-                    }
-                    if let Some(else_clause) = &body.else_clause {
-                        self.traverse_statements_to_validate(
-                            flatten_statements,
-                            else_clause.statement,
-                        );
-                    }
-                }
-                IfBody::ColonDelimited(body) => {
-                    for s in body.statements.iter() {
-                        self.traverse_statements_to_validate(flatten_statements, s);
-                    }
-                    for clauses in body.else_if_clauses.iter() {
-                        for s in clauses.statements.iter() {
-                            self.traverse_statements_to_validate(flatten_statements, s);
-                        }
-                    }
-                    if let Some(else_clause) = &body.else_clause {
-                        for s in else_clause.statements.iter() {
-                            self.traverse_statements_to_validate(flatten_statements, s);
-                        }
-                    }
-                }
-            },
-            Statement::While(while_stmt) => match &while_stmt.body {
-                WhileBody::Statement(body) => {
-                    self.traverse_statements_to_validate(flatten_statements, body);
-                }
-                WhileBody::ColonDelimited(body) => {
-                    for s in body.statements.iter() {
-                        self.traverse_statements_to_validate(flatten_statements, s);
-                    }
-                }
-            },
-            Statement::DoWhile(do_while_stmt) => {
-                self.traverse_statements_to_validate(flatten_statements, do_while_stmt.statement);
-            }
-            Statement::Foreach(foreach_stmt) => match &foreach_stmt.body {
-                ForeachBody::Statement(body) => {
-                    self.traverse_statements_to_validate(flatten_statements, body);
-                }
-                ForeachBody::ColonDelimited(body) => {
-                    for s in body.statements.iter() {
-                        self.traverse_statements_to_validate(flatten_statements, s);
-                    }
-                }
-            },
-            Statement::For(for_stmt) => match &for_stmt.body {
-                ForBody::Statement(body) => {
-                    self.traverse_statements_to_validate(flatten_statements, body);
-                }
-                ForBody::ColonDelimited(body) => {
-                    for s in body.statements.iter() {
-                        self.traverse_statements_to_validate(flatten_statements, s);
-                    }
-                }
-            },
-            Statement::Try(try_stmt) => {
-                for s in try_stmt.block.statements.iter() {
-                    self.traverse_statements_to_validate(flatten_statements, s);
-                }
-                for catch in try_stmt.catch_clauses.iter() {
-                    for s in catch.block.statements.iter() {
-                        self.traverse_statements_to_validate(flatten_statements, s);
-                    }
-                }
-                if let Some(finally) = &try_stmt.finally_clause {
-                    for s in finally.block.statements.iter() {
-                        self.traverse_statements_to_validate(flatten_statements, s);
-                    }
-                }
-            }
-            Statement::Namespace(namespace) => {
-                for s in namespace.statements().iter() {
-                    self.traverse_statements_to_validate(flatten_statements, s);
-                }
-            }
-            Statement::Class(class) => {
-                for member in class.members.iter() {
-                    if let ClassLikeMember::Method(method) = member {
-                        if let MethodBody::Concrete(block) = &method.body {
-                            for s in block.statements.iter() {
-                                self.traverse_statements_to_validate(flatten_statements, s);
-                            }
-                        }
-                    }
-                }
-            }
-            Statement::Interface(interface) => {
-                for member in interface.members.iter() {
-                    if let ClassLikeMember::Method(method) = member {
-                        if let MethodBody::Concrete(block) = &method.body {
-                            for s in block.statements.iter() {
-                                self.traverse_statements_to_validate(flatten_statements, s);
-                            }
-                        }
-                    }
-                }
-            }
-            Statement::Trait(t) => {
-                for member in t.members.iter() {
-                    if let ClassLikeMember::Method(method) = member {
-                        if let MethodBody::Concrete(block) = &method.body {
-                            for s in block.statements.iter() {
-                                self.traverse_statements_to_validate(flatten_statements, s);
-                            }
-                        }
-                    }
-                }
+/// `statement` and every statement nested in it, those in functions,
+/// closures and class methods included.
+pub(crate) fn flatten_statements<'ast, 'arena>(
+    statement: &'ast Statement<'arena>,
+) -> Vec<&'ast Statement<'arena>> {
+    struct Statements<'ast, 'arena>(Vec<&'ast Statement<'arena>>);
+
+    impl<'ast, 'arena> Visitor<'ast, 'arena> for Statements<'ast, 'arena> {
+        fn enter(&mut self, node: Node<'ast, 'arena>, _scope: &Scope) -> bool {
+            if let Node::Statement(statement) = node {
+                self.0.push(statement);
             }
-            Statement::Enum(e) => {
-                for member in e.members.iter() {
-                    if let ClassLikeMember::Method(method) = member {
-                        if let MethodBody::Concrete(block) = &method.body {
-                            for s in block.statements.iter() {
-                                self.traverse_statements_to_validate(flatten_statements, s);
-                            }
-                        }
-                    }
-                }
-            }
-            _ => {}
+            true
         }
     }
+
+    let mut statements = Statements(Vec::new());
+    visitor::walk(Node::Statement(statement), &mut statements);
+    statements.0
 }
 
 pub(crate) fn do_validate_namespace(
@@ -312,6 +203,132 @@ pub(crate) fn do_validate_namespace(
     true
 }
 
+/// Fully qualified name of a class referenced or declared as `name` at `span`.
+pub(crate) fn resolve_class_name(file: &File<'_>, name: &[u8], span: Span) -> String {
+    file.resolve_class_name(&String::from_utf8_lossy(name), span.start.offset)
+}
+
+/// Name and members of a class, interface, trait or enum declaration.
+pub(crate) fn class_like<'a, 'arena>(
+    statement: &'a Statement<'arena>,
+) -> Option<(
+    &'a LocalIdentifier<'arena>,
+    &'a Sequence<'arena, ClassLikeMember<'arena>>,
+)> {
+    match statement {
+        Statement::Class(class) => Some((&class.name, &class.members)),
+        Statement::Interface(interface) => Some((&interface.name, &interface.members)),
+        Statement::Trait(t) => Some((&t.name, &t.members)),
+        Statement::Enum(e) => Some((&e.name, &e.members)),
+        _ => None,
+    }
+}
+
+/// A standalone function, closure or arrow function.
+pub(crate) enum FunctionLike<'ast, 'arena> {
+    Function(&'ast Function<'arena>),
+    Closure(&'ast Closure<'arena>),
+    ArrowFunction(&'ast ArrowFunction<'arena>),
+}
+
+impl<'ast, 'arena> FunctionLike<'ast, 'arena> {
+    pub(crate) fn from_node(node: &Node<'ast, 'arena>) -> Option<Self> {
+        match node {
+            Node::Function(function) => Some(FunctionLike::Function(function)),
+            Node::Closure(closure) => Some(FunctionLike::Closure(closure)),
+            Node::ArrowFunction(arrow) => Some(FunctionLike::ArrowFunction(arrow)),
+            _ => None,
+        }
+    }
+
+    /// The declared name, or `{closure}` / `{arrow function}`.
+    pub(crate) fn name(&self) -> String {
+        match self {
+            FunctionLike::Function(function) => {
+                String::from_utf8_lossy(function.name.value).into_owned()
+            }
+            FunctionLike::Closure(_) => String::from("{closure}"),
+            FunctionLike::ArrowFunction(_) => String::from("{arrow function}"),
+        }
+    }
+
+    pub(crate) fn parameter_list(&self) -> &'ast FunctionLikeParameterList<'arena> {
+        match self {
+            FunctionLike::Function(function) => &function.parameter_list,
+            FunctionLike::Closure(closure) => &closure.parameter_list,
+            FunctionLike::ArrowFunction(arrow) => &arrow.parameter_list,
+        }
+    }
+
+    /// The body block; arrow functions have a single expression instead.
+    pub(crate) fn body(&self) -> Option<&'ast Block<'arena>> {
+        match self {
+            FunctionLike::Function(function) => Some(&function.body),
+            FunctionLike::Closure(closure) => Some(&closure.body),
+            FunctionLike::ArrowFunction(_) => None,
+        }
+    }
+
+    /// The expression an arrow function returns.
+    pub(crate) fn expression(&self) -> Option<&'arena Expression<'arena>> {
+        match self {
+            FunctionLike::ArrowFunction(arrow) => Some(arrow.expression),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn span(&self) -> Span {
+        match self {
+            FunctionLike::Function(function) => function.span(),
+            FunctionLike::Closure(closure) => closure.span(),
+            FunctionLike::ArrowFunction(arrow) => arrow.span(),
+        }
+    }
+}
+
+/// Functions, closures and arrow functions in `statement`, nested ones included.
+pub(crate) fn function_likes<'ast, 'arena>(
+    statement: &'ast Statement<'arena>,
+) -> Vec<FunctionLike<'ast, 'arena>> {
+    find_nodes(statement, FunctionLike::from_node)
+}
+
+/// Nodes of `statement` picked by `f`, in source order.
+///
+/// Nested statements are skipped, as `flatten_statements` hands them to the
+/// rule on their own.
+pub(crate) fn find_nodes<'ast, 'arena, T>(
+    statement: &'ast Statement<'arena>,
+    f: impl Fn(&Node<'ast, 'arena>) -> Option<T>,
+) -> Vec<T> {
+    struct Find<'ast, 'arena, F, T> {
+        root: &'ast Statement<'arena>,
+        f: F,
+        found: Vec<T>,
+    }
+
+    impl<'ast, 'arena, F, T> Visitor<'ast, 'arena> for Find<'ast, 'arena, F, T>
+    where
+        F: Fn(&Node<'ast, 'arena>) -> Option<T>,
+    {
+        fn enter(&mut self, node: Node<'ast, 'arena>, _scope: &Scope) -> bool {
+            if matches!(node, Node::Statement(s) if !std::ptr::eq(s, self.root)) {
+                return false;
+            }
+            self.found.extend((self.f)(&node));
+            true
+        }
+    }
+
+    let mut find = Find {
+        root: statement,
+        f,
+        found: Vec::new(),
+    };
+    visitor::walk(Node::Statement(statement), &mut find);
+    find.found
+}
+
 fn add_rule(rules: &mut HashMap<String, Box<dyn Rule>>, rule: Box<dyn Rule>) {
     rules.insert(rule.get_code(), rule as Box<dyn Rule>);
 }
@@ -350,6 +367,7 @@ pub fn all_rules() -> HashMap<String, Box<dyn Rule>> {
     add_rule(&mut rules, Box::default() as Box<e28::Rule>);
     add_rule(&mut rules, Box::default() as Box<e29::Rule>);
     add_rule(&mut rules, Box::default() as Box<e30::Rule>);
+    add_rule(&mut rules, Box::new(e31::Rule {}));
 
     rules
 }
@@ -358,8 +376,10 @@ pub fn all_rules() -> HashMap<String, Box<dyn Rule>> {
 mod tests {
     use mago_allocator::prelude::LocalArena;
     use std::fs;
+    use std::sync::Arc;
 
     use crate::analyse::Analyse;
+    use crate::project_index::ProjectIndex;
 
     use super::*;
 
@@ -379,9 +399,7 @@ mod tests {
         };
         let analyse = Analyse::new(&config);
 
-        for rule in analyse.rules.values() {
-            rule.index_file(&file);
-        }
+        file.project = Some(Arc::new(ProjectIndex::build([&file])));
 
         analyse.analyse_file(&mut file, false).0
     }
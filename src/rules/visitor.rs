@@ -0,0 +1,205 @@
+//! Depth-first walk over the syntax tree with enter/leave callbacks for every
+//! statement and expression, tracking the class, method, function and loops
+//! each node sits in.
+
+use mago_syntax::cst::*;
+
+use crate::rules::{guard, FunctionLike};
+
+/// Where a node sits. A class, method, function or loop node is itself
+/// visited with the scope around it; its children see the scope it opens.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Scope {
+    /// Enclosing class, interface, trait or enum; `class@anonymous` for
+    /// anonymous classes.
+    pub(crate) class: Option<String>,
+    pub(crate) method: Option<String>,
+    /// Enclosing function, `{closure}` or `{arrow function}`.
+    pub(crate) function: Option<String>,
+    /// Loops around the node within its function or method.
+    pub(crate) loop_depth: usize,
+}
+
+impl Scope {
+    /// The scope the children of `node` see, when it differs from this one.
+    fn opened_by(&self, node: &Node<'_, '_>) -> Option<Scope> {
+        let class = |name: &[u8]| Scope {
+            class: Some(String::from_utf8_lossy(name).into_owned()),
+            ..Default::default()
+        };
+
+        let scope = match node {
+            Node::Class(class_node) => class(class_node.name.value),
+            Node::Interface(interface) => class(interface.name.value),
+            Node::Trait(t) => class(t.name.value),
+            Node::Enum(e) => class(e.name.value),
+            Node::AnonymousClass(_) => class(b"class@anonymous"),
+            Node::Method(method) => Scope {
+                class: self.class.clone(),
+                method: Some(String::from_utf8_lossy(method.name.value).into_owned()),
+                ..Default::default()
+            },
+            Node::For(_) | Node::Foreach(_) | Node::While(_) | Node::DoWhile(_) => Scope {
+                loop_depth: self.loop_depth + 1,
+                ..self.clone()
+            },
+            node => Scope {
+                function: Some(FunctionLike::from_node(node)?.name()),
+                loop_depth: 0,
+                ..self.clone()
+            },
+        };
+        Some(scope)
+    }
+}
+
+pub(crate) trait Visitor<'ast, 'arena> {
+    /// Called before the children of `node`. Returning false skips them,
+    /// and `leave` is not called for `node`.
+    fn enter(&mut self, _node: Node<'ast, 'arena>, _scope: &Scope) -> bool {
+        true
+    }
+
+    /// Called after the children of `node`, with the scope `enter` saw.
+    fn leave(&mut self, _node: Node<'ast, 'arena>, _scope: &Scope) {}
+}
+
+enum Step<'ast, 'arena> {
+    Enter(Node<'ast, 'arena>),
+    /// Leave a node, restoring the scope outside it when it opened one.
+    Leave(Node<'ast, 'arena>, Option<Scope>),
+}
+
+/// Visit `node` and everything below it, in source order.
+pub(crate) fn walk<'ast, 'arena>(
+    node: Node<'ast, 'arena>,
+    visitor: &mut impl Visitor<'ast, 'arena>,
+) {
+    // Iterative, as long fluent chains nest deeper than the stack allows.
+    let mut scope = Scope::default();
+    let mut stack = vec![Step::Enter(node)];
+    let mut steps = 0usize;
+    while let Some(step) = stack.pop() {
+        steps += 1;
+        if steps.is_multiple_of(1024) {
+            guard::check_deadline();
+        }
+        match step {
+            Step::Enter(node) => {
+                if !visitor.enter(node, &scope) {
+                    continue;
+                }
+                let outer = scope
+                    .opened_by(&node)
+                    .map(|inner| std::mem::replace(&mut scope, inner));
+                stack.push(Step::Leave(node, outer));
+
+                let first_child = stack.len();
+                node.visit_children(|child| stack.push(Step::Enter(child)));
+                stack[first_child..].reverse();
+            }
+            Step::Leave(node, outer) => {
+                if let Some(outer) = outer {
+                    scope = outer;
+                }
+                visitor.leave(node, &scope);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mago_allocator::prelude::LocalArena;
+    use std::path::PathBuf;
+
+    use crate::file::File;
+
+    use super::*;
+
+    /// Scope of every `echo`; `depth` is back to 0 once every node is left.
+    #[derive(Default)]
+    struct Echoes {
+        depth: usize,
+        found: Vec<Scope>,
+    }
+
+    impl<'ast, 'arena> Visitor<'ast, 'arena> for Echoes {
+        fn enter(&mut self, node: Node<'ast, 'arena>, scope: &Scope) -> bool {
+            self.depth += 1;
+            if let Node::Echo(_) = node {
+                self.found.push(scope.clone());
+            }
+            true
+        }
+
+        fn leave(&mut self, _node: Node<'ast, 'arena>, _scope: &Scope) {
+            self.depth -= 1;
+        }
+    }
+
+    #[test]
+    fn tracks_scope() {
+        let code = r#"<?php
+class Report {
+    public function render(array $rows) {
+        foreach ($rows as $row) {
+            array_map(function ($cell) {
+                while (true) {
+                    echo $cell;
+                }
+            }, $row);
+            echo $row;
+        }
+    }
+}
+echo 'done';
+"#;
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("report.php"), code.to_string());
+
+        let mut echoes = Echoes::default();
+        walk(Node::Program(file.ast.unwrap()), &mut echoes);
+
+        let scope = |method: Option<&str>, function: Option<&str>, loop_depth| Scope {
+            class: method.map(|_| "Report".to_string()),
+            method: method.map(str::to_string),
+            function: function.map(str::to_string),
+            loop_depth,
+        };
+        assert_eq!(
+            echoes.found,
+            vec![
+                scope(Some("render"), Some("{closure}"), 1),
+                scope(Some("render"), None, 1),
+                scope(None, None, 0),
+            ]
+        );
+        assert_eq!(echoes.depth, 0);
+    }
+
+    #[derive(Default)]
+    struct Statements(usize);
+
+    impl<'ast, 'arena> Visitor<'ast, 'arena> for Statements {
+        fn enter(&mut self, node: Node<'ast, 'arena>, _scope: &Scope) -> bool {
+            if let Node::Statement(_) = node {
+                self.0 += 1;
+            }
+            !matches!(node, Node::Closure(_))
+        }
+    }
+
+    #[test]
+    fn enter_can_skip_children() {
+        let code = "<?php\n$f = function () { return 1; };\nreturn 2;\n";
+        let arena = LocalArena::new();
+        let file = File::new(&arena, PathBuf::from("skip.php"), code.to_string());
+
+        let mut statements = Statements::default();
+        walk(Node::Program(file.ast.unwrap()), &mut statements);
+
+        // The opening tag and the two top-level statements, not the closure body.
+        assert_eq!(statements.0, 3);
+    }
+}
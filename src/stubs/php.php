@@ -0,0 +1,449 @@
+<?php
+
+// Declarations of PHP core and common extension classes and functions, indexed
+// as the fallback of every `ProjectIndex` lookup. Only signatures matter here:
+// bodies are empty, and error returns (`|false`) are left out of return types,
+// as a chained call assumes the previous one succeeded.
+
+interface Traversable {}
+
+interface Iterator extends Traversable
+{
+    public function current(): mixed;
+    public function key(): mixed;
+    public function next(): void;
+    public function rewind(): void;
+    public function valid(): bool;
+}
+
+interface IteratorAggregate extends Traversable
+{
+    public function getIterator(): Iterator;
+}
+
+interface ArrayAccess
+{
+    public function offsetExists(mixed $offset): bool;
+    public function offsetGet(mixed $offset): mixed;
+    public function offsetSet(mixed $offset, mixed $value): void;
+    public function offsetUnset(mixed $offset): void;
+}
+
+interface Countable
+{
+    public function count(): int;
+}
+
+interface Stringable
+{
+    public function __toString(): string;
+}
+
+interface JsonSerializable
+{
+    public function jsonSerialize(): mixed;
+}
+
+interface UnitEnum
+{
+    public static function cases(): array;
+}
+
+interface BackedEnum extends UnitEnum
+{
+    public static function from(int|string $value): static;
+    public static function tryFrom(int|string $value): ?static;
+}
+
+final class stdClass {}
+
+// Exceptions and errors
+
+interface Throwable extends Stringable
+{
+    public function getMessage(): string;
+    public function getCode(): mixed;
+    public function getFile(): string;
+    public function getLine(): int;
+    public function getTrace(): array;
+    public function getTraceAsString(): string;
+    public function getPrevious(): ?Throwable;
+}
+
+class Exception implements Throwable
+{
+    protected $message = "";
+    protected $code = 0;
+    protected string $file = "";
+    protected int $line = 0;
+
+    public function __construct(string $message = "", int $code = 0, ?Throwable $previous = null) {}
+    public function getMessage(): string {}
+    public function getCode(): mixed {}
+    public function getFile(): string {}
+    public function getLine(): int {}
+    public function getTrace(): array {}
+    public function getTraceAsString(): string {}
+    public function getPrevious(): ?Throwable {}
+    public function __toString(): string {}
+}
+
+class Error implements Throwable
+{
+    protected $message = "";
+    protected $code = 0;
+    protected string $file = "";
+    protected int $line = 0;
+
+    public function __construct(string $message = "", int $code = 0, ?Throwable $previous = null) {}
+    public function getMessage(): string {}
+    public function getCode(): mixed {}
+    public function getFile(): string {}
+    public function getLine(): int {}
+    public function getTrace(): array {}
+    public function getTraceAsString(): string {}
+    public function getPrevious(): ?Throwable {}
+    public function __toString(): string {}
+}
+
+class ErrorException extends Exception
+{
+    public function getSeverity(): int {}
+}
+
+class CompileError extends Error {}
+class ParseError extends CompileError {}
+class TypeError extends Error {}
+class ArgumentCountError extends TypeError {}
+class ValueError extends Error {}
+class ArithmeticError extends Error {}
+class DivisionByZeroError extends ArithmeticError {}
+class AssertionError extends Error {}
+class UnhandledMatchError extends Error {}
+
+class LogicException extends Exception {}
+class BadFunctionCallException extends LogicException {}
+class BadMethodCallException extends BadFunctionCallException {}
+class DomainException extends LogicException {}
+class InvalidArgumentException extends LogicException {}
+class LengthException extends LogicException {}
+class OutOfRangeException extends LogicException {}
+
+class RuntimeException extends Exception {}
+class OutOfBoundsException extends RuntimeException {}
+class OverflowException extends RuntimeException {}
+class RangeException extends RuntimeException {}
+class UnderflowException extends RuntimeException {}
+class UnexpectedValueException extends RuntimeException {}
+
+class JsonException extends Exception {}
+
+// Closures and generators
+
+final class Closure
+{
+    public static function bind(Closure $closure, ?object $newThis, object|string|null $newScope = "static"): ?Closure {}
+    public function bindTo(?object $newThis, object|string|null $newScope = "static"): ?Closure {}
+    public function call(object $newThis, mixed ...$args): mixed {}
+    public static function fromCallable(callable $callback): Closure {}
+}
+
+final class Generator implements Iterator
+{
+    public function current(): mixed {}
+    public function key(): mixed {}
+    public function next(): void {}
+    public function rewind(): void {}
+    public function valid(): bool {}
+    public function send(mixed $value): mixed {}
+    public function throw(Throwable $exception): mixed {}
+    public function getReturn(): mixed {}
+}
+
+// date
+
+interface DateTimeInterface
+{
+    const ATOM = "Y-m-d\\TH:i:sP";
+    const ISO8601 = "Y-m-d\\TH:i:sO";
+    const RFC3339 = "Y-m-d\\TH:i:sP";
+
+    public function format(string $format): string;
+    public function getTimezone(): DateTimeZone;
+    public function getOffset(): int;
+    public function getTimestamp(): int;
+    public function diff(DateTimeInterface $targetObject, bool $absolute = false): DateInterval;
+}
+
+class DateTime implements DateTimeInterface
+{
+    public function __construct(string $datetime = "now", ?DateTimeZone $timezone = null) {}
+    public static function createFromFormat(string $format, string $datetime, ?DateTimeZone $timezone = null): DateTime {}
+    public static function createFromImmutable(DateTimeImmutable $object): static {}
+    public static function createFromInterface(DateTimeInterface $object): DateTime {}
+    public function format(string $format): string {}
+    public function modify(string $modifier): DateTime {}
+    public function add(DateInterval $interval): DateTime {}
+    public function sub(DateInterval $interval): DateTime {}
+    public function setDate(int $year, int $month, int $day): DateTime {}
+    public function setISODate(int $year, int $week, int $dayOfWeek = 1): DateTime {}
+    public function setTime(int $hour, int $minute, int $second = 0, int $microsecond = 0): DateTime {}
+    public function setTimestamp(int $timestamp): DateTime {}
+    public function setTimezone(DateTimeZone $timezone): DateTime {}
+    public function getTimezone(): DateTimeZone {}
+    public function getOffset(): int {}
+    public function getTimestamp(): int {}
+    public function diff(DateTimeInterface $targetObject, bool $absolute = false): DateInterval {}
+}
+
+class DateTimeImmutable implements DateTimeInterface
+{
+    public function __construct(string $datetime = "now", ?DateTimeZone $timezone = null) {}
+    public static function createFromFormat(string $format, string $datetime, ?DateTimeZone $timezone = null): DateTimeImmutable {}
+    public static function createFromMutable(DateTime $object): static {}
+    public static function createFromInterface(DateTimeInterface $object): DateTimeImmutable {}
+    public function format(string $format): string {}
+    public function modify(string $modifier): DateTimeImmutable {}
+    public function add(DateInterval $interval): DateTimeImmutable {}
+    public function sub(DateInterval $interval): DateTimeImmutable {}
+    public function setDate(int $year, int $month, int $day): DateTimeImmutable {}
+    public function setISODate(int $year, int $week, int $dayOfWeek = 1): DateTimeImmutable {}
+    public function setTime(int $hour, int $minute, int $second = 0, int $microsecond = 0): DateTimeImmutable {}
+    public function setTimestamp(int $timestamp): DateTimeImmutable {}
+    public function setTimezone(DateTimeZone $timezone): DateTimeImmutable {}
+    public function getTimezone(): DateTimeZone {}
+    public function getOffset(): int {}
+    public function getTimestamp(): int {}
+    public function diff(DateTimeInterface $targetObject, bool $absolute = false): DateInterval {}
+}
+
+class DateTimeZone
+{
+    const UTC = 1024;
+
+    public function __construct(string $timezone) {}
+    public function getName(): string {}
+    public function getOffset(DateTimeInterface $datetime): int {}
+    public function getLocation(): array {}
+}
+
+class DateInterval
+{
+    public int $y;
+    public int $m;
+    public int $d;
+    public int $h;
+    public int $i;
+    public int $s;
+    public float $f;
+    public int $invert;
+    public mixed $days;
+
+    public function __construct(string $duration) {}
+    public static function createFromDateString(string $datetime): DateInterval {}
+    public function format(string $format): string {}
+}
+
+class DatePeriod implements IteratorAggregate
+{
+    public function __construct(DateTimeInterface $start, DateInterval $interval, int|DateTimeInterface $end, int $options = 0) {}
+    public function getStartDate(): DateTimeInterface {}
+    public function getEndDate(): ?DateTimeInterface {}
+    public function getDateInterval(): DateInterval {}
+    public function getIterator(): Iterator {}
+}
+
+function date_create(string $datetime = "now", ?DateTimeZone $timezone = null): DateTime {}
+function date_create_immutable(string $datetime = "now", ?DateTimeZone $timezone = null): DateTimeImmutable {}
+function date_diff(DateTimeInterface $baseObject, DateTimeInterface $targetObject, bool $absolute = false): DateInterval {}
+function date_interval_create_from_date_string(string $datetime): DateInterval {}
+function timezone_open(string $timezone): DateTimeZone {}
+
+// SPL
+
+class ArrayIterator implements Iterator, ArrayAccess, Countable
+{
+    public function __construct(array|object $array = [], int $flags = 0) {}
+    public function current(): mixed {}
+    public function key(): mixed {}
+    public function next(): void {}
+    public function rewind(): void {}
+    public function valid(): bool {}
+    public function offsetExists(mixed $key): bool {}
+    public function offsetGet(mixed $key): mixed {}
+    public function offsetSet(mixed $key, mixed $value): void {}
+    public function offsetUnset(mixed $key): void {}
+    public function count(): int {}
+    public function getArrayCopy(): array {}
+}
+
+class ArrayObject implements IteratorAggregate, ArrayAccess, Countable
+{
+    public function __construct(array|object $array = [], int $flags = 0, string $iteratorClass = "ArrayIterator") {}
+    public function getIterator(): ArrayIterator {}
+    public function offsetExists(mixed $key): bool {}
+    public function offsetGet(mixed $key): mixed {}
+    public function offsetSet(mixed $key, mixed $value): void {}
+    public function offsetUnset(mixed $key): void {}
+    public function count(): int {}
+    public function append(mixed $value): void {}
+    public function getArrayCopy(): array {}
+}
+
+class SplObjectStorage implements Countable, Iterator, ArrayAccess
+{
+    public function attach(object $object, mixed $info = null): void {}
+    public function detach(object $object): void {}
+    public function contains(object $object): bool {}
+    public function count(int $mode = 0): int {}
+    public function current(): object {}
+    public function key(): int {}
+    public function next(): void {}
+    public function rewind(): void {}
+    public function valid(): bool {}
+    public function offsetExists($object): bool {}
+    public function offsetGet($object): mixed {}
+    public function offsetSet($object, mixed $info = null): void {}
+    public function offsetUnset($object): void {}
+}
+
+class SplFileInfo implements Stringable
+{
+    public function __construct(string $filename) {}
+    public function getFilename(): string {}
+    public function getPath(): string {}
+    public function getPathname(): string {}
+    public function getExtension(): string {}
+    public function getRealPath(): string {}
+    public function getSize(): int {}
+    public function isDir(): bool {}
+    public function isFile(): bool {}
+    public function getFileInfo(?string $class = null): SplFileInfo {}
+    public function openFile(string $mode = "r"): SplFileObject {}
+    public function __toString(): string {}
+}
+
+class SplFileObject extends SplFileInfo implements Iterator
+{
+    public function eof(): bool {}
+    public function fgets(): string {}
+    public function fwrite(string $data, int $length = 0): int {}
+    public function current(): string|array|false {}
+    public function key(): int {}
+    public function next(): void {}
+    public function rewind(): void {}
+    public function valid(): bool {}
+}
+
+function iterator_to_array(Traversable|array $iterator, bool $preserve_keys = true): array {}
+
+// json
+
+function json_encode(mixed $value, int $flags = 0, int $depth = 512): string {}
+function json_decode(string $json, ?bool $associative = null, int $depth = 512, int $flags = 0): mixed {}
+
+// PDO
+
+class PDO
+{
+    const PARAM_NULL = 0;
+    const PARAM_INT = 1;
+    const PARAM_STR = 2;
+    const PARAM_BOOL = 5;
+    const FETCH_ASSOC = 2;
+    const FETCH_NUM = 3;
+    const FETCH_OBJ = 5;
+    const FETCH_COLUMN = 7;
+    const FETCH_CLASS = 8;
+    const ATTR_ERRMODE = 3;
+    const ERRMODE_EXCEPTION = 2;
+
+    public function __construct(string $dsn, ?string $username = null, ?string $password = null, ?array $options = null) {}
+    public function prepare(string $query, array $options = []): PDOStatement {}
+    public function query(string $query, ?int $fetchMode = null): PDOStatement {}
+    public function exec(string $statement): int {}
+    public function quote(string $string, int $type = 2): string {}
+    public function lastInsertId(?string $name = null): string {}
+    public function beginTransaction(): bool {}
+    public function commit(): bool {}
+    public function rollBack(): bool {}
+    public function inTransaction(): bool {}
+    public function getAttribute(int $attribute): mixed {}
+    public function setAttribute(int $attribute, mixed $value): bool {}
+    public function errorCode(): ?string {}
+    public function errorInfo(): array {}
+}
+
+class PDOStatement implements IteratorAggregate
+{
+    public function execute(?array $params = null): bool {}
+    public function bindValue(string|int $param, mixed $value, int $type = 2): bool {}
+    public function bindParam(string|int $param, mixed &$var, int $type = 2, int $maxLength = 0, mixed $driverOptions = null): bool {}
+    public function fetch(int $mode = 0, int $cursorOrientation = 0, int $cursorOffset = 0): mixed {}
+    public function fetchAll(int $mode = 0, mixed ...$args): array {}
+    public function fetchColumn(int $column = 0): mixed {}
+    public function fetchObject(?string $class = "stdClass", array $constructorArgs = []): object {}
+    public function setFetchMode(int $mode, mixed ...$args): bool {}
+    public function rowCount(): int {}
+    public function columnCount(): int {}
+    public function closeCursor(): bool {}
+    public function getIterator(): Iterator {}
+}
+
+class PDOException extends RuntimeException
+{
+    public ?array $errorInfo = null;
+}
+
+// mysqli
+
+class mysqli
+{
+    public function __construct(?string $hostname = null, ?string $username = null, ?string $password = null, ?string $database = null, ?int $port = null, ?string $socket = null) {}
+    public function prepare(string $query): mysqli_stmt {}
+    public function query(string $query, int $result_mode = 0): mysqli_result {}
+    public function real_escape_string(string $string): string {}
+    public function begin_transaction(int $flags = 0, ?string $name = null): bool {}
+    public function commit(int $flags = 0, ?string $name = null): bool {}
+    public function rollback(int $flags = 0, ?string $name = null): bool {}
+    public function close(): bool {}
+}
+
+class mysqli_stmt
+{
+    public function bind_param(string $types, mixed &...$vars): bool {}
+    public function execute(?array $params = null): bool {}
+    public function get_result(): mysqli_result {}
+    public function close(): bool {}
+}
+
+class mysqli_result implements IteratorAggregate
+{
+    public function fetch_assoc(): ?array {}
+    public function fetch_all(int $mode = 2): array {}
+    public function fetch_object(string $class = "stdClass", array $constructor_args = []): ?object {}
+    public function free(): void {}
+    public function getIterator(): Iterator {}
+}
+
+function mysqli_connect(?string $hostname = null, ?string $username = null, ?string $password = null, ?string $database = null, ?int $port = null, ?string $socket = null): mysqli {}
+
+// SimpleXML
+
+class SimpleXMLElement implements Stringable, Countable, IteratorAggregate
+{
+    public function __construct(string $data, int $options = 0, bool $dataIsURL = false, string $namespaceOrPrefix = "", bool $isPrefix = false) {}
+    public function xpath(string $expression): array {}
+    public function children(?string $namespaceOrPrefix = null, bool $isPrefix = false): ?SimpleXMLElement {}
+    public function attributes(?string $namespaceOrPrefix = null, bool $isPrefix = false): ?SimpleXMLElement {}
+    public function addChild(string $qualifiedName, ?string $value = null, ?string $namespace = null): ?SimpleXMLElement {}
+    public function addAttribute(string $qualifiedName, string $value, ?string $namespace = null): void {}
+    public function asXML(?string $filename = null): string {}
+    public function getName(): string {}
+    public function count(): int {}
+    public function getIterator(): Iterator {}
+    public function __toString(): string {}
+}
+
+function simplexml_load_string(string $data, ?string $class_name = "SimpleXMLElement", int $options = 0, string $namespace_or_prefix = "", bool $is_prefix = false): SimpleXMLElement {}
+function simplexml_load_file(string $filename, ?string $class_name = "SimpleXMLElement", int $options = 0, string $namespace_or_prefix = "", bool $is_prefix = false): SimpleXMLElement {}